@@ -0,0 +1,65 @@
+//! Script to annotate a complete Connect Four game with the quality of each move.
+//!
+//! This script replays a game with the `GameAnalyser`, printing the classification and score
+//! of every move alongside the best moves available, followed by a summary of each player's
+//! accuracy. The script must be run with the game's move sequence as a command-line argument,
+//! given as a string of 1-based columns. An optional second argument runs the analysis with
+//! the possible moves at each ply solved in parallel.
+
+use connect_four_ai::{GameAnalyser, MoveClassification, Position, Solver};
+use std::env;
+use std::error::Error;
+use std::time::Instant;
+
+/// Main entrypoint for the game analysis binary.
+fn main() -> Result<(), Box<dyn Error>> {
+    // Collects and parses command-line arguments
+    let args: Vec<String> = env::args().collect();
+    let Some(moves) = args.get(1) else {
+        eprintln!("Error: Missing command-line argument.");
+        eprintln!("Usage: cargo run --release --bin analyse_game -- <moves> [parallel]");
+        return Err("No moves given".into());
+    };
+    let parallel = args.get(2).is_some_and(|arg| arg == "parallel");
+
+    let mut solver = Solver::new();
+    solver.parallel_move_scores = parallel;
+    let mut analyser = GameAnalyser::with_solver(solver);
+
+    println!("Analysing game '{moves}'...");
+    let start_time = Instant::now();
+    let report = analyser.analyse(moves)?;
+    let duration = start_time.elapsed();
+
+    println!("\n{:>4}  {:<6}  {:>4}  {:>5}  {:>4}  {:<10}  Best moves", "Ply", "Player", "Move", "Score", "Best", "Class");
+    for annotation in report.annotations.iter() {
+        let best_moves: Vec<String> = annotation.best_moves.iter().map(|column| (column + 1).to_string()).collect();
+        println!(
+            "{:>4}  {:<6}  {:>4}  {:>5}  {:>4}  {:<10}  {}",
+            annotation.ply + 1,
+            annotation.player,
+            annotation.column + 1,
+            annotation.score,
+            annotation.best_score,
+            annotation.classification.to_string(),
+            best_moves.join(" "),
+        );
+    }
+
+    println!();
+    for player in [1, 2] {
+        let counts: Vec<String> = MoveClassification::ALL
+            .iter()
+            .map(|&classification| format!("{} {classification}", report.count(player, classification)))
+            .collect();
+        println!("Player {player}: {:.1}% accuracy ({})", report.accuracy[player as usize - 1], counts.join(", "));
+    }
+
+    match report.winner {
+        Some(winner) => println!("Player {winner} won the game."),
+        None if report.annotations.len() == Position::BOARD_SIZE => println!("The game was drawn."),
+        None => println!("The game is unfinished."),
+    }
+    println!("Analysed {} moves in {:?}", report.annotations.len(), duration);
+    Ok(())
+}
\ No newline at end of file
@@ -9,8 +9,36 @@
 //!    start of the game to reach the desired position.
 //! 2. Expected score: The known best score for that position from the current player's
 //!    perspective.
+//!
+//! If the `--statistics` flag is given anywhere on the command line, the benchmark also
+//! reports detailed search statistics collected by the solver, such as transposition table
+//! hit rates and cut-off counts. Collecting statistics slows the search down, so the timings
+//! of runs with and without the flag should not be compared. Similarly, the
+//! `--no-enhanced-cutoffs` flag disables the solver's enhanced transposition cut-offs, to
+//! measure the positions they save. The `--check` flag makes the benchmark exit with an
+//! error if any position is given the wrong score, so that it can be used to check the
+//! correctness of a configuration over a whole test set. The `--hint` flag solves each
+//! position with its expected score as a hint, to measure the positions saved by the
+//! solver's aspiration windows.
+//!
+//! An optional number of search threads can be given as a second command-line argument
+//! (default: 1), which is used to check the correctness of the multithreaded search.
+//! A transposition table replacement policy can be given as a third argument (one of
+//! `always-replace`, `two-tier` or `cluster`; default: `always-replace`) to compare the policies.
+//! Similarly, a move ordering strategy can be given as a fourth argument (one of `threats`,
+//! `history`, `killer` or `tt-move`; default: `threats`).
 
-use connect_four_ai::{Position, Solver};
+use connect_four_ai::{
+    HistoryOrdering,
+    KillerOrdering,
+    MoveOrdering,
+    Position,
+    ReplacementPolicy,
+    SearchStatistics,
+    Solver,
+    TTMoveFirst,
+    ThreatOrdering,
+};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -50,15 +78,29 @@ struct BenchmarkResults {
     total_duration: Duration,
     total_positions_explored: usize,
     failures: Vec<(String, i8, i8)>, // (moves, expected, actual)
+    statistics: Option<SearchStatistics>,
+    iteration_times: Vec<(Duration, usize)>, // (total time, count) for each binary search iteration
 }
 
 impl BenchmarkResults {
     /// Updates the results with data from a single test run.
-    fn update(&mut self, moves: &str, expected: i8, actual: i8, duration: Duration, positions: usize) {
+    fn update(&mut self, moves: &str, expected: i8, actual: i8, duration: Duration, positions: usize, statistics: Option<&SearchStatistics>) {
         self.total_tests += 1;
         self.total_duration += duration;
         self.total_positions_explored += positions;
 
+        // Aggregates the search statistics, keeping iteration times separate for each iteration
+        if let Some(statistics) = statistics {
+            self.statistics.get_or_insert_with(SearchStatistics::new).merge(statistics);
+            for (i, &time) in statistics.iteration_times.iter().enumerate() {
+                if i == self.iteration_times.len() {
+                    self.iteration_times.push((Duration::ZERO, 0));
+                }
+                self.iteration_times[i].0 += time;
+                self.iteration_times[i].1 += 1;
+            }
+        }
+
         if expected == actual {
             self.correct_solves += 1;
         } else {
@@ -87,6 +129,10 @@ impl Display for BenchmarkResults {
             writeln!(f, "Mean time per position: {mean_time:?}")?;
             writeln!(f, "Mean nodes explored: {mean_nodes:.0}")?;
             writeln!(f, "Solver speed: {k_pos_per_sec:.2} kpos/s")?;
+
+            if let Some(statistics) = &self.statistics {
+                write!(f, "{}", StatisticsReport(self, statistics))?;
+            }
         }
 
         if !self.failures.is_empty() {
@@ -100,30 +146,114 @@ impl Display for BenchmarkResults {
     }
 }
 
+/// A wrapper for displaying the aggregated search statistics from a benchmark run.
+struct StatisticsReport<'a>(&'a BenchmarkResults, &'a SearchStatistics);
+
+impl Display for StatisticsReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let StatisticsReport(results, statistics) = *self;
+
+        writeln!(f, "\n--- Search Statistics ---")?;
+        writeln!(
+            f,
+            "Book hits: {} / {} lookups",
+            statistics.book_hits,
+            statistics.book_hits + statistics.book_misses
+        )?;
+        writeln!(
+            f,
+            "TT hits: {} / {} probes ({:.2}%)",
+            statistics.tt_hits,
+            statistics.tt_probes,
+            statistics.tt_hit_rate() * 100.0
+        )?;
+        writeln!(f, "Enhanced transposition cut-offs: {}", statistics.enhanced_cutoffs)?;
+
+        let total_cutoffs = statistics.total_cutoffs().max(1) as f64;
+        writeln!(f, "Cut-offs by move index:")?;
+        for (i, &count) in statistics.cutoffs.iter().enumerate() {
+            writeln!(f, "  Move {}: {count:>12} ({:>6.2}%)", i + 1, count as f64 / total_cutoffs * 100.0)?;
+        }
+
+        writeln!(f, "Nodes per depth:")?;
+        for (depth, &count) in statistics.nodes_per_depth.iter().enumerate().rev() {
+            if count > 0 {
+                writeln!(f, "  Depth {depth:>2}: {count:>12}")?;
+            }
+        }
+
+        writeln!(f, "Mean time per search iteration:")?;
+        for (i, &(total_time, count)) in results.iteration_times.iter().enumerate() {
+            writeln!(f, "  Iteration {:>2}: {:?} ({count} searches)", i + 1, total_time / count as u32)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Main entrypoint for the benchmark binary.
 fn main() -> Result<(), Box<dyn Error>> {
-    // Collects and parses command-line arguments
-    let path = match env::args().nth(1) {
-        Some(p) => p,
+    // Collects and parses command-line arguments, separating flags from positional arguments
+    let (flags, args): (Vec<String>, Vec<String>) = env::args().partition(|arg| arg.starts_with("--"));
+    const FLAGS: [&str; 4] = ["--statistics", "--no-enhanced-cutoffs", "--check", "--hint"];
+    if let Some(flag) = flags.iter().find(|flag| !FLAGS.contains(&flag.as_str())) {
+        return Err(format!("Unknown flag '{flag}'. Expected one of {FLAGS:?}.").into());
+    }
+    let check_scores = flags.iter().any(|flag| flag == "--check");
+    let use_hints = flags.iter().any(|flag| flag == "--hint");
+    let collect_statistics = flags.iter().any(|flag| flag == "--statistics");
+    let enhanced_cutoffs = !flags.iter().any(|flag| flag == "--no-enhanced-cutoffs");
+    let path = match args.get(1) {
+        Some(p) => p.clone(),
         None => {
             eprintln!("Error: Missing command-line argument.");
-            eprintln!("Usage: cargo run --release --bin benchmark -- <path/to/test_file>");
+            eprintln!("Usage: cargo run --release --bin benchmark -- <path/to/test_file> [threads] [policy] [ordering] [--statistics] [--no-enhanced-cutoffs] [--check] [--hint]");
             return Err("No path given".into());
         }
     };
+    let threads = match args.get(2) {
+        Some(arg) => arg.parse::<usize>()?.max(1),
+        None => 1,
+    };
+    let policy = match args.get(3) {
+        Some(arg) => arg.parse::<ReplacementPolicy>()?,
+        None => ReplacementPolicy::default(),
+    };
+    let ordering = match args.get(4) {
+        Some(arg) => parse_move_ordering(arg)?,
+        None => Box::new(ThreatOrdering),
+    };
 
     println!("Loading test data from '{path}'...");
     let test_cases = load_test_data(&path)?;
 
-    println!("Running benchmark on {} positions...", test_cases.len());
-    let results = run_benchmark(&test_cases)?;
+    println!(
+        "Running benchmark on {} positions using {threads} thread(s), the {policy:?} policy and {} move ordering...",
+        test_cases.len(),
+        args.get(4).map_or("threats", String::as_str),
+    );
+    let results = run_benchmark(&test_cases, threads, policy, ordering, enhanced_cutoffs, collect_statistics, use_hints)?;
 
     // Prints the final, formatted benchmark report
     println!("{results}");
 
+    if check_scores && !results.failures.is_empty() {
+        return Err(format!("{} positions were given the wrong score", results.failures.len()).into());
+    }
     Ok(())
 }
 
+/// Parses the name of a move ordering strategy.
+fn parse_move_ordering(name: &str) -> Result<Box<dyn MoveOrdering>, Box<dyn Error>> {
+    match name {
+        "threats" => Ok(Box::new(ThreatOrdering)),
+        "history" => Ok(Box::new(HistoryOrdering::new())),
+        "killer" => Ok(Box::new(KillerOrdering::new())),
+        "tt-move" => Ok(Box::new(TTMoveFirst(ThreatOrdering))),
+        _ => Err(format!("Unknown move ordering '{name}'. Expected 'threats', 'history', 'killer' or 'tt-move'.").into()),
+    }
+}
+
 /// Loads and parses a given test file into a vector of `TestCase` structs.
 fn load_test_data(path: &str) -> Result<Vec<(String, TestCase)>, Box<dyn Error>> {
     let file = File::open(path)?;
@@ -145,9 +275,25 @@ fn load_test_data(path: &str) -> Result<Vec<(String, TestCase)>, Box<dyn Error>>
 }
 
 /// Runs a Connect Four solver against all test cases and aggregates the results.
-fn run_benchmark(test_cases: &[(String, TestCase)]) -> Result<BenchmarkResults, Box<dyn Error>> {
+fn run_benchmark(
+    test_cases: &[(String, TestCase)],
+    threads: usize,
+    policy: ReplacementPolicy,
+    ordering: Box<dyn MoveOrdering>,
+    enhanced_cutoffs: bool,
+    collect_statistics: bool,
+    use_hints: bool,
+) -> Result<BenchmarkResults, Box<dyn Error>> {
     let mut results = BenchmarkResults::default();
-    let mut solver = Solver::new();
+    let mut solver = Solver::builder()
+        .threads(threads)
+        .replacement_policy(policy)
+        .enhanced_cutoffs(enhanced_cutoffs)
+        .build()?;
+    solver.move_ordering = ordering;
+    if collect_statistics {
+        solver.enable_statistics();
+    }
 
     let progress_bar = create_progress_bar(test_cases.len() as u64);
 
@@ -155,10 +301,15 @@ fn run_benchmark(test_cases: &[(String, TestCase)]) -> Result<BenchmarkResults,
         solver.reset();
 
         let start_time = Instant::now();
-        let actual_score = solver.solve(&test_case.position);
+        let actual_score = if use_hints {
+            solver.solve_with_hint(&test_case.position, test_case.expected_score)
+        } else {
+            solver.solve(&test_case.position)
+        };
         let duration = start_time.elapsed();
 
-        results.update(line_str, test_case.expected_score, actual_score, duration, solver.explored_positions);
+        let statistics = solver.statistics.as_ref();
+        results.update(line_str, test_case.expected_score, actual_score, duration, solver.explored_positions, statistics);
     }
 
     Ok(results)
@@ -1,25 +1,33 @@
 //! A script to analyse and tune the AI player's difficulty settings.
 //!
 //! The script must be run with a position's move sequence string specified as the first
-//! command-line argument - if not specified, an empty position will be used.
+//! command-line argument - if not specified, an empty position will be used. The engine used
+//! to score the position's moves can be given as a second argument, as one of `solver`,
+//! `heuristic`, `fallback` or `mcts` (default: `solver`).
 
-use connect_four_ai::{AIPlayer, Difficulty, Position};
+use connect_four_ai::{AIPlayer, Difficulty, Engine, FallbackEngine, HeuristicEngine, MctsEngine, Position, SearchLimits, Solver};
 use std::collections::HashMap;
 use std::env;
 use std::error::Error;
 
 const SIMULATION_COUNT: u32 = 10000;
 
+/// The number of nodes the fallback engine's solver may search for each move before falling back.
+const FALLBACK_NODE_LIMIT: usize = 1_000_000;
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Collects and parses command-line arguments
     let args: Vec<String> = env::args().collect();
     let pos_moves = args.get(1)
         .map_or("", |s| s);
+    let engine_name = args.get(2)
+        .map_or("solver", |s| s);
 
     println!(
-        "Running {} simulations for position: '{}'...",
+        "Running {} simulations for position: '{}' using the {} engine...",
         SIMULATION_COUNT,
-        if pos_moves.is_empty() { "Empty Position" } else { pos_moves }
+        if pos_moves.is_empty() { "Empty Position" } else { pos_moves },
+        engine_name,
     );
 
     let difficulties = [
@@ -28,7 +36,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         Difficulty::Hard,
         Difficulty::Impossible,
     ];
-    let mut players: Vec<_> = difficulties.iter().map(|&d| AIPlayer::new(d)).collect();
+    let mut players = Vec::with_capacity(difficulties.len());
+    for &difficulty in difficulties.iter() {
+        players.push(AIPlayer::with_engine(difficulty, create_engine(engine_name)?));
+    }
     let position = Position::from_moves(pos_moves)?;
 
     // Determines the move scores for the position, and calculates the optimal score from them
@@ -118,3 +129,18 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Creates the engine with the given name, which is used to score the position's moves.
+fn create_engine(name: &str) -> Result<Box<dyn Engine>, Box<dyn Error>> {
+    match name {
+        "solver" => Ok(Box::new(Solver::new())),
+        "heuristic" => Ok(Box::new(HeuristicEngine::default())),
+        "fallback" => {
+            let mut solver = Solver::new();
+            solver.limits = SearchLimits::new().with_max_nodes(FALLBACK_NODE_LIMIT);
+            Ok(Box::new(FallbackEngine::new(solver, HeuristicEngine::default())))
+        }
+        "mcts" => Ok(Box::new(MctsEngine::default())),
+        _ => Err(format!("Unknown engine '{name}'. Expected 'solver', 'heuristic', 'fallback' or 'mcts'.").into()),
+    }
+}
@@ -0,0 +1,80 @@
+//! Script to check the rule sets found by the Connect Four AI's rule-based evaluator.
+//!
+//! This script runs the `RuleEvaluator` on a set of predefined test positions, and checks
+//! every rule set it finds against the position's known score, as a rule set should only be
+//! found if the second player doesn't lose. The script must be run with a path to a testing
+//! file as a command-line argument, in the same format as the files used by the `benchmark`
+//! script. An optional second argument prints the rule set found for each position.
+
+use connect_four_ai::{Position, RuleEvaluator};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Main entrypoint for the rule evaluator checking binary.
+fn main() -> Result<(), Box<dyn Error>> {
+    // Collects and parses command-line arguments
+    let args: Vec<String> = env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("Error: Missing command-line argument.");
+        eprintln!("Usage: cargo run --release --bin explain_rules -- <path/to/test_file> [verbose]");
+        return Err("No path given".into());
+    };
+    let verbose = args.get(2).is_some_and(|arg| arg == "verbose");
+
+    println!("Loading test data from '{path}'...");
+    let mut evaluator = RuleEvaluator::new();
+    let mut total_duration = Duration::ZERO;
+    let mut found = 0;
+    let mut test_count = 0;
+    let mut failures = Vec::new();
+
+    for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+        let mut parts = line.split_whitespace();
+        let (Some(moves), Some(score)) = (parts.next(), parts.next()) else { continue };
+        let position = Position::from_moves(moves)
+            .map_err(|e| format!("Error parsing line {}: {}", i + 1, e))?;
+        let score = score.parse::<i8>()?;
+        test_count += 1;
+
+        let start_time = Instant::now();
+        let rule_set = evaluator.find_rules(&position);
+        total_duration += start_time.elapsed();
+        let Some(rule_set) = rule_set else { continue };
+        found += 1;
+
+        // Converts the score to the second player's perspective
+        let second_player_score = if position.get_moves().is_multiple_of(2) { -score } else { score };
+        if second_player_score < 0 || (rule_set.second_player_wins && second_player_score == 0) {
+            failures.push(format!("Moves: '{moves}' | Score: {score}, Got: {rule_set:?}"));
+        }
+
+        if verbose {
+            println!("\n{moves} (score {score}):");
+            if let Some(column) = rule_set.first_move {
+                println!("  Second player plays column {}", column + 1);
+            }
+            for application in rule_set.rules.iter() {
+                let squares: Vec<String> = application.squares.iter().map(|square| square.to_string()).collect();
+                println!("  {} on {} refutes {} groups", application.rule, squares.join(" "), application.refuted_groups.len());
+            }
+        }
+    }
+
+    println!(
+        "\nFound rule sets for {found}/{test_count} positions in {:?} (mean {:?})",
+        total_duration,
+        total_duration / test_count.max(1),
+    );
+    if failures.is_empty() {
+        println!("Every rule set is consistent with the known scores.");
+        Ok(())
+    } else {
+        println!("{} rule sets contradict the known scores:", failures.len());
+        for failure in failures.iter().take(10) {
+            println!("  {failure}");
+        }
+        Err("Rule sets contradict the known scores".into())
+    }
+}
\ No newline at end of file
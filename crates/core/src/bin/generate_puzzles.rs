@@ -0,0 +1,56 @@
+//! Script to generate a set of Connect Four training puzzles.
+//!
+//! This script runs the `PuzzleGenerator` to find puzzles in random reachable positions, and
+//! prints one puzzle per line, giving its move sequence, kind, depth, difficulty and solution
+//! line. The script must be run with the number of puzzles to generate as a command-line
+//! argument. Optional arguments give the generator's seed, the kind of puzzle ('win', 'draw'
+//! or 'any') and the difficulty of puzzle ('easy', 'medium', 'hard', 'impossible' or 'any').
+
+use connect_four_ai::{Difficulty, PuzzleFilter, PuzzleGenerator, PuzzleKind};
+use std::env;
+use std::error::Error;
+use std::time::Instant;
+
+/// Main entrypoint for the puzzle generation binary.
+fn main() -> Result<(), Box<dyn Error>> {
+    // Collects and parses command-line arguments
+    let args: Vec<String> = env::args().collect();
+    let Some(count) = args.get(1) else {
+        eprintln!("Error: Missing command-line argument.");
+        eprintln!("Usage: cargo run --release --bin generate_puzzles -- <count> [seed] [kind] [difficulty]");
+        return Err("No puzzle count given".into());
+    };
+    let count = count.parse::<usize>()?;
+
+    let mut generator = match args.get(2) {
+        Some(seed) => PuzzleGenerator::with_seed(seed.parse::<u64>()?),
+        None => PuzzleGenerator::new(),
+    };
+
+    let mut filter = PuzzleFilter::new();
+    match args.get(3).map(|arg| arg.as_str()) {
+        Some("win") => filter = filter.with_kind(PuzzleKind::Win),
+        Some("draw") => filter = filter.with_kind(PuzzleKind::Draw),
+        Some("any") | None => {}
+        Some(kind) => return Err(format!("Unknown puzzle kind '{kind}'").into()),
+    }
+    match args.get(4).map(|arg| arg.as_str()) {
+        Some("easy") => filter = filter.with_difficulty(Difficulty::Easy),
+        Some("medium") => filter = filter.with_difficulty(Difficulty::Medium),
+        Some("hard") => filter = filter.with_difficulty(Difficulty::Hard),
+        Some("impossible") => filter = filter.with_difficulty(Difficulty::Impossible),
+        Some("any") | None => {}
+        Some(difficulty) => return Err(format!("Unknown difficulty '{difficulty}'").into()),
+    }
+    generator.filter = filter;
+
+    eprintln!("Generating {count} puzzles...");
+    let start_time = Instant::now();
+    let puzzles = generator.generate(count);
+    for puzzle in puzzles.iter() {
+        println!("{puzzle}");
+    }
+
+    eprintln!("Generated {} puzzles in {:?}", puzzles.len(), start_time.elapsed());
+    Ok(())
+}
\ No newline at end of file
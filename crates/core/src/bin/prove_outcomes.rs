@@ -0,0 +1,78 @@
+//! Script to cross-validate the outcome provers of the Connect Four AI engine.
+//!
+//! This script runs the `ProofNumberSolver` and the negamax `Solver` on a set of predefined
+//! test positions, asking each whether every position is won and whether it is lost, and
+//! checks their answers against the sign of the position's known score. The script must be
+//! run with a path to a testing file as a command-line argument, in the same format as the
+//! files used by the `benchmark` script.
+
+use connect_four_ai::{OutcomeProver, Position, ProofNumberSolver, Solver};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Main entrypoint for the outcome prover cross-validation binary.
+fn main() -> Result<(), Box<dyn Error>> {
+    // Collects and parses command-line arguments
+    let path = match env::args().nth(1) {
+        Some(p) => p,
+        None => {
+            eprintln!("Error: Missing command-line argument.");
+            eprintln!("Usage: cargo run --release --bin prove_outcomes -- <path/to/test_file>");
+            return Err("No path given".into());
+        }
+    };
+
+    println!("Loading test data from '{path}'...");
+    let mut test_cases = Vec::new();
+    for (i, line) in fs::read_to_string(&path)?.lines().enumerate() {
+        let mut parts = line.split_whitespace();
+        let (Some(moves), Some(score)) = (parts.next(), parts.next()) else { continue };
+        let position = Position::from_moves(moves)
+            .map_err(|e| format!("Error parsing line {}: {}", i + 1, e))?;
+        test_cases.push((moves.to_string(), position, score.parse::<i8>()?));
+    }
+
+    // The solver is used without its opening book, so that every answer is searched
+    let provers: Vec<(&str, Box<dyn OutcomeProver>)> = vec![
+        ("ProofNumberSolver", Box::new(ProofNumberSolver::new())),
+        ("Solver", Box::new(Solver::empty())),
+    ];
+
+    let mut all_agree = true;
+    for (name, mut prover) in provers {
+        println!("\nRunning {name} on {} positions...", test_cases.len());
+        let mut total_duration = Duration::ZERO;
+        let mut failures = Vec::new();
+
+        for (moves, position, score) in &test_cases {
+            let start_time = Instant::now();
+            let is_win = prover.is_win(position);
+            let is_loss = prover.is_loss(position);
+            total_duration += start_time.elapsed();
+
+            if is_win != (*score > 0) || is_loss != (*score < 0) {
+                failures.push(format!("Moves: '{moves}' | Score: {score}, Got: win = {is_win}, loss = {is_loss}"));
+            }
+        }
+
+        println!(
+            "Correct: {} / {} | Mean time per position: {:?}",
+            test_cases.len() - failures.len(),
+            test_cases.len(),
+            total_duration / test_cases.len().max(1) as u32
+        );
+        for failure in &failures {
+            println!("{failure}");
+        }
+        all_agree &= failures.is_empty();
+    }
+
+    if all_agree {
+        println!("\nAll provers agree with the expected outcomes.");
+        Ok(())
+    } else {
+        Err("Some provers disagree with the expected outcomes".into())
+    }
+}
\ No newline at end of file
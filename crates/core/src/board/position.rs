@@ -210,15 +210,75 @@ impl Position {
     /// positions will always have the same solution.
     #[inline(always)]
     pub fn get_key(&self) -> u64 {
+        self.get_canonical_key().0
+    }
+
+    /// Returns the same key as `get_key`, along with whether the key is that of the position's
+    /// mirror image.
+    ///
+    /// Columns relating to a mirrored key, such as a best move stored alongside it, must be
+    /// mirrored with `Position::mirror_column` to convert them to and from the position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver, TTStore};
+    ///
+    ///  let pos = Position::from_moves("5554224333234511764415115").unwrap();
+    ///  let mirrored = Position::from_moves("3334664555654377124473773").unwrap();
+    ///  let (key, is_mirrored) = pos.get_canonical_key();
+    ///  assert_eq!(mirrored.get_canonical_key(), (key, !is_mirrored));
+    ///
+    ///  // The best move stored after solving one position is the best move of both positions
+    ///  // once converted from the key's orientation
+    ///  let mut solver = Solver::builder()
+    ///      .transposition_table_memory(4)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///  assert_eq!(solver.solve(&mirrored), 4);
+    ///  for (position, best_move) in [(pos, 5), (mirrored, 1)] {
+    ///      let (key, is_mirrored) = position.get_canonical_key();
+    ///      let stored_move = solver.transposition_table.get(key).unwrap().best_move.unwrap();
+    ///      let column = if is_mirrored { Position::mirror_column(stored_move) } else { stored_move };
+    ///      assert_eq!(column, best_move);
+    ///  }
+    /// ```
+    #[inline(always)]
+    pub fn get_canonical_key(&self) -> (u64, bool) {
         // Calculates the standard key for a position
-        let key = self.position + self.mask;
+        let key = self.get_unmirrored_key();
 
         // Calculates the key of the mirrored position
         let (mirrored_pos, mirrored_mask) = self.get_mirrored_bitmasks();
         let mirrored_key = mirrored_pos + mirrored_mask;
 
         // Takes the minimum to ensure that symmetrical positions resolve to the same key
-        key.min(mirrored_key)
+        if mirrored_key < key {
+            (mirrored_key, true)
+        } else {
+            (key, false)
+        }
+    }
+
+    /// Returns the column which a given column is mapped to in the position's mirror image.
+    ///
+    /// # Arguments
+    ///
+    /// * `col`: 0-based index of a column.
+    #[inline(always)]
+    pub const fn mirror_column(col: usize) -> usize {
+        Self::WIDTH - 1 - col
+    }
+
+    /// Returns a unique key for the current position, which distinguishes it from its mirror
+    /// image.
+    ///
+    /// This key is needed wherever mirrored positions may have different solutions, such as
+    /// when a solver's moves are restricted by an asymmetric `MoveFilter`.
+    #[inline(always)]
+    pub fn get_unmirrored_key(&self) -> u64 {
+        self.position + self.mask
     }
 
     /// Returns both of the positions' bitmasks, mirrored horizontally.
@@ -320,12 +380,17 @@ impl Position {
     }
 
     /// Returns a mask for the current player's winning positions.
-    fn winning_positions(&self) -> u64 {
+    ///
+    /// These are the empty cells which would complete a 4-alignment for the current player,
+    /// including floating cells which can't be played yet, so the mask is also a mask of the
+    /// player's threats.
+    pub fn winning_positions(&self) -> u64 {
         Self::compute_winning_positions(self.position, self.mask)
     }
 
-    /// Returns a mask for the opponent's winning positions.
-    fn opponent_winning_positions(&self) -> u64 {
+    /// Returns a mask for the opponent's winning positions, which is also a mask of the
+    /// opponent's threats.
+    pub fn opponent_winning_positions(&self) -> u64 {
         Self::compute_winning_positions(self.position ^ self.mask, self.mask)
     }
 
@@ -1,4 +1,4 @@
-//! Provides the logic for an AI player which utilises a solver to select which move to play
+//! Provides the logic for an AI player which utilises an engine to select which move to play
 //! in a given Connect Four position.
 //!
 //! The AI's skill is determined by the `Difficulty` enum, which controls the temperature of
@@ -10,7 +10,7 @@ use std::path::Path;
 use rand::distr::weighted::WeightedIndex;
 use rand::{rng};
 use rand::distr::Distribution;
-use crate::{Position, Solver};
+use crate::{Engine, Handicap, Position, SearchObserver, Solver};
 
 /// An enum to represent the difficulty of an AI player.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -33,52 +33,199 @@ impl Difficulty {
     }
 }
 
-/// An AI player that uses a solver to determine the best move to play in a Connect Four position.
+/// An AI player that uses an engine to determine the best move to play in a Connect Four
+/// position. By default, the engine is a perfect `Solver`, but any `Engine` can be used.
 ///
 /// The player's skill level can be configured using the `Difficulty` enum, which adjusts the
 /// move selection strategy.
+///
+/// Between turns, the player remembers the score of the move it selected. If the next position
+/// it is asked to move in follows on from that move, the score is used as a hint to speed up
+/// the search for the scores of its moves.
+///
+/// A `Handicap` can be set to restrict the player's opening move, such as forcing it to open
+/// in an edge column. Games with a handicap should start from the handicap's `position`.
 #[derive(Debug)]
-pub struct AIPlayer {
-    solver: Solver,
+pub struct AIPlayer<E = Solver> {
+    engine: E,
     difficulty: Difficulty,
+    handicap: Handicap,
+    /// The position after the player's previous move, along with the score of that move, which
+    /// is the expected score of the positions following the opponent's reply.
+    previous_move: Option<(Position, i8)>,
 }
 
 impl AIPlayer {
     /// Creates a new AI player with a default solver and specified difficulty.
     pub fn new(difficulty: Difficulty) -> AIPlayer {
-        AIPlayer {
-            solver: Solver::new(),
-            difficulty,
-        }
+        Self::with_solver(difficulty, Solver::new())
+    }
+
+    /// Creates a new AI player which uses the given solver, such as one created with a
+    /// `SolverBuilder` to limit the size of its transposition table.
+    pub fn with_solver(difficulty: Difficulty, solver: Solver) -> AIPlayer {
+        Self::with_engine(difficulty, solver)
+    }
+
+    /// Returns a reference to the AI player's solver.
+    pub fn solver(&self) -> &Solver {
+        &self.engine
+    }
+
+    /// Returns a mutable reference to the AI player's solver.
+    pub fn solver_mut(&mut self) -> &mut Solver {
+        &mut self.engine
+    }
+
+    /// Sets whether the AI player evaluates the possible moves in each position concurrently.
+    ///
+    /// Parallel evaluation produces identical move scores, but solves each move on a separate
+    /// thread, which can greatly reduce the time taken to select moves in complex positions.
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.engine.parallel_move_scores = parallel;
     }
 
     /// Attempts to load an opening book from the given path for the AI player's solver.
     ///
     /// Returns whether the opening book was successfully loaded.
     pub fn load_opening_book(&mut self, path: &Path) -> bool {
-        self.solver.load_opening_book(path)
+        self.engine.load_opening_book(path)
+    }
+
+    /// Attaches an observer to the AI player's solver, which receives progress reports
+    /// while the player searches for a move.
+    pub fn set_observer(&mut self, observer: impl SearchObserver + 'static) {
+        self.engine.set_observer(observer);
+    }
+}
+
+impl<E: Engine> AIPlayer<E> {
+    /// Creates a new AI player with the specified difficulty, which uses the given engine to
+    /// score its moves.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{AIPlayer, Difficulty, Engine, Position, Solver};
+    ///
+    ///  // Any engine can be used, including boxed trait objects chosen at runtime
+    ///  let engine: Box<dyn Engine> = Box::new(Solver::new());
+    ///  let mut player = AIPlayer::with_engine(Difficulty::Impossible, engine);
+    ///
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///  assert_eq!(player.engine().name(), "Solver");
+    ///  assert_eq!(player.get_move(&pos), Some(4));
+    /// ```
+    pub fn with_engine(difficulty: Difficulty, engine: E) -> AIPlayer<E> {
+        AIPlayer {
+            engine,
+            difficulty,
+            handicap: Handicap::None,
+            previous_move: None,
+        }
+    }
+
+    /// Returns a reference to the AI player's engine.
+    pub fn engine(&self) -> &E {
+        &self.engine
+    }
+
+    /// Returns a mutable reference to the AI player's engine.
+    pub fn engine_mut(&mut self) -> &mut E {
+        &mut self.engine
+    }
+
+    /// Returns the handicap applied to the AI player's moves.
+    pub fn handicap(&self) -> Handicap {
+        self.handicap
     }
 
-    /// Resets the AI player's solver.
+    /// Sets the handicap applied to the AI player's moves, which forbids any moves the
+    /// handicap doesn't allow from being scored or selected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{AIPlayer, Difficulty, Handicap};
+    ///
+    ///  let mut player = AIPlayer::new(Difficulty::Impossible);
+    ///  player.set_handicap(Handicap::EdgeOpening);
+    ///
+    ///  let pos = Handicap::EdgeOpening.position().unwrap();
+    ///  let scores = player.get_all_move_scores(&pos);
+    ///  assert_eq!(scores, [Some(-2), None, None, None, None, None, Some(-2)]);
+    ///  assert!(matches!(player.get_move(&pos), Some(0 | 6)));
+    /// ```
+    pub fn set_handicap(&mut self, handicap: Handicap) {
+        self.handicap = handicap;
+    }
+
+    /// Resets the AI player's engine, and forgets the score of its previous move.
     pub fn reset(&mut self) {
-        self.solver.reset();
+        self.engine.reset();
+        self.previous_move = None;
     }
 
-    /// Solves a position to find its exact score using the AI player's solver.
+    /// Evaluates a position to find its score using the AI player's engine. With the default
+    /// solver, this is the position's exact score.
     pub fn solve(&mut self, position: &Position) -> i8 {
-        self.solver.solve(position)
+        self.engine.evaluate(position)
     }
 
     /// Calculates the scores for all possible next moves in the given position using the
-    /// AI player's solver.
+    /// AI player's engine.
     pub fn get_all_move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
-        self.solver.get_all_move_scores(position)
+        let mut scores = self.engine.move_scores(position);
+        self.handicap.restrict_move_scores(position, &mut scores);
+        scores
+    }
+
+    /// Calculates the scores for all possible next moves in a position whose score is expected
+    /// to be known, using the AI player's engine. See `Solver::get_all_move_scores_with_hint`.
+    pub fn get_all_move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        let mut scores = self.engine.move_scores_with_hint(position, expected_score);
+        self.handicap.restrict_move_scores(position, &mut scores);
+        scores
     }
 
     /// Solves and selects the AI player's move for the given position.
+    ///
+    /// If the position follows on from the player's previous move, the score of that move is
+    /// used as a hint for the position's score. The selected move's score is then remembered
+    /// for the next turn.
     pub fn get_move(&mut self, position: &Position) -> Option<usize> {
-        let move_scores = self.solver.get_all_move_scores(position);
-        self.select_move(position, &move_scores)
+        let move_scores = match self.expected_score(position) {
+            Some(expected_score) => self.get_all_move_scores_with_hint(position, expected_score),
+            None => self.get_all_move_scores(position),
+        };
+        let selected = self.select_move(position, &move_scores);
+
+        // Remembers the position after the selected move, and the score it is expected to have
+        self.previous_move = selected.and_then(|column| {
+            let mut next_position = *position;
+            next_position.play(column);
+            move_scores[column].map(|score| (next_position, score))
+        });
+        selected
+    }
+
+    /// Returns the expected score of a position, if it was reached by the opponent replying to
+    /// the player's previous move.
+    ///
+    /// Assuming the opponent replies with their best move, the score of the position is the
+    /// score of the player's previous move.
+    fn expected_score(&self, position: &Position) -> Option<i8> {
+        let (previous_position, score) = self.previous_move?;
+        let is_reply = position.get_moves() == previous_position.get_moves() + 1
+            && (0..Position::WIDTH).any(|column| {
+                let mut reply = previous_position;
+                if !reply.is_playable(column) {
+                    return false;
+                }
+                reply.play(column);
+                reply.position == position.position && reply.mask == position.mask
+            });
+        is_reply.then_some(score)
     }
 
     /// Selects a move from a fixed-size array of scores using a Softmax distribution with a
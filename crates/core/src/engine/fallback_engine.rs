@@ -0,0 +1,118 @@
+//! Provides an engine which solves positions perfectly when possible, falling back to a
+//! heuristic search when the solver exceeds its limits.
+
+use crate::{Engine, HeuristicEngine, Position, Solver};
+use super::search_engine::best_scored_move;
+
+/// An engine which uses a `Solver` within its search limits, and falls back to a
+/// `HeuristicEngine` for positions which can't be solved perfectly within them.
+///
+/// Each search is run with `Solver::try_solve`, so the limits are taken from the solver's
+/// `limits` field. When scoring moves, the limits apply to the search of each move, and if
+/// any move can't be solved, every move is scored by the fallback engine instead, so that
+/// exact scores and estimates are never compared with each other.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{BookSource, Engine, FallbackEngine, HeuristicEngine, Position, SearchLimits, Solver};
+///
+///  let solver = Solver::builder()
+///      .opening_book(BookSource::None)
+///      .limits(SearchLimits::new().with_max_nodes(10_000))
+///      .build()
+///      .unwrap();
+///  let mut engine = FallbackEngine::new(solver, HeuristicEngine::new(6));
+///
+///  // Early positions can't be solved within the limits, so they are estimated
+///  let pos = Position::from_moves("4453").unwrap();
+///  assert!(engine.best_move(&pos).is_some());
+///  assert!(engine.used_fallback);
+///
+///  // Late positions are solved perfectly
+///  let pos = Position::from_moves("7422341735647741166133573473242566").unwrap();
+///  assert_eq!(engine.evaluate(&pos), 1);
+///  assert!(!engine.used_fallback);
+/// ```
+#[derive(Debug)]
+pub struct FallbackEngine {
+    /// The solver used for positions which can be solved within its limits.
+    pub solver: Solver,
+
+    /// The engine used for positions which can't be solved within the solver's limits.
+    pub fallback: HeuristicEngine,
+
+    /// Whether the last evaluation fell back to the heuristic engine.
+    pub used_fallback: bool,
+}
+
+impl FallbackEngine {
+    /// Creates a new engine from a solver, whose limits decide when to fall back, and a
+    /// heuristic engine to fall back to.
+    pub fn new(solver: Solver, fallback: HeuristicEngine) -> FallbackEngine {
+        FallbackEngine { solver, fallback, used_fallback: false }
+    }
+
+    /// Attempts to solve each possible move in a position within the solver's limits,
+    /// returning `None` if any move couldn't be solved.
+    fn try_move_scores(&mut self, position: &Position) -> Option<[Option<i8>; Position::WIDTH]> {
+        let mut scores = [None; Position::WIDTH];
+
+        // If the game is won or the position is full, no moves are possible
+        if position.is_won_position() || position.get_moves() == Position::BOARD_SIZE {
+            return Some(scores);
+        }
+
+        for (column, score) in scores.iter_mut().enumerate() {
+            if !position.is_playable(column) {
+                continue;
+            }
+            if position.is_winning_move(column) {
+                *score = Some((Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2);
+            } else {
+                let mut child = *position;
+                child.play(column);
+                *score = Some(-self.solver.try_solve(&child)?);
+            }
+        }
+
+        Some(scores)
+    }
+}
+
+impl Engine for FallbackEngine {
+    fn name(&self) -> &str {
+        "Solver with heuristic fallback"
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        let score = self.solver.try_solve(position);
+        self.used_fallback = score.is_none();
+        score.unwrap_or_else(|| self.fallback.evaluate(position))
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        let scores = self.try_move_scores(position);
+        self.used_fallback = scores.is_none();
+        scores.unwrap_or_else(|| self.fallback.move_scores(position))
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        match self.try_move_scores(position) {
+            Some(scores) => {
+                self.used_fallback = false;
+                best_scored_move(&scores)
+            }
+            None => {
+                self.used_fallback = true;
+                self.fallback.best_move(position)
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.solver.reset();
+        self.fallback.reset();
+        self.used_fallback = false;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,302 @@
+//! Provides an analyser which annotates every move of a game with its quality.
+
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::{Outcome, Position, PositionParsingError, Solver};
+
+/// The classification of a move, from its score compared with the best move's score.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MoveClassification {
+    /// A move achieving the best possible score.
+    Best,
+    /// A move keeping the outcome, while losing at most the analyser's `good_score_loss`.
+    Good,
+    /// A move keeping the outcome, but losing more than the analyser's `good_score_loss`.
+    Inaccuracy,
+    /// A move throwing away a win for a draw.
+    Mistake,
+    /// A move turning a win or a draw into a loss.
+    Blunder,
+}
+
+impl MoveClassification {
+    /// All move classifications, from best to worst.
+    pub const ALL: [MoveClassification; 5] = [
+        MoveClassification::Best,
+        MoveClassification::Good,
+        MoveClassification::Inaccuracy,
+        MoveClassification::Mistake,
+        MoveClassification::Blunder,
+    ];
+}
+
+impl Display for MoveClassification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MoveClassification::Best => "Best",
+            MoveClassification::Good => "Good",
+            MoveClassification::Inaccuracy => "Inaccuracy",
+            MoveClassification::Mistake => "Mistake",
+            MoveClassification::Blunder => "Blunder",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The annotation of a single move in an analysed game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveAnnotation {
+    /// The 0-based index of the move in the game.
+    pub ply: usize,
+
+    /// The player making the move, which is 1 for the first player and 2 for the second.
+    pub player: u8,
+
+    /// The 0-based column of the move.
+    pub column: usize,
+
+    /// The exact score of the move, from the perspective of the player making it.
+    pub score: i8,
+
+    /// The best score which could have been achieved in the position.
+    pub best_score: i8,
+
+    /// The 0-based columns of every move achieving the best score, from the centre outwards.
+    pub best_moves: Vec<usize>,
+
+    /// The move's accuracy as a percentage, from 0 for the worst possible move to 100 for a
+    /// best move.
+    pub accuracy: f64,
+
+    /// The classification of the move.
+    pub classification: MoveClassification,
+}
+
+/// A structured report of an analysed game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameReport {
+    /// The analysed game's move sequence.
+    pub moves: String,
+
+    /// The annotation of each move in the game, in the order they were played.
+    pub annotations: Vec<MoveAnnotation>,
+
+    /// The mean accuracy of each player's moves as a percentage, with the first player's
+    /// accuracy first. A player who made no moves has an accuracy of 100.
+    pub accuracy: [f64; 2],
+
+    /// The player who won the game, or `None` if it was drawn or is unfinished.
+    pub winner: Option<u8>,
+}
+
+impl GameReport {
+    /// Returns the number of moves the given player made with the given classification.
+    pub fn count(&self, player: u8, classification: MoveClassification) -> usize {
+        self.annotations
+            .iter()
+            .filter(|annotation| annotation.player == player && annotation.classification == classification)
+            .count()
+    }
+}
+
+/// An analyser which replays a game, solving every possible move at each ply to annotate the
+/// quality of the moves which were played.
+///
+/// Each move is classified by comparing its outcome and score with the best move available:
+/// - Moves achieving the best score are **best**
+/// - Moves keeping the best outcome are **good** if they lose at most `good_score_loss`, and
+///   **inaccuracies** otherwise. As scores count the moves remaining when the game is won,
+///   an inaccuracy is a win which takes much longer, or a loss which comes much sooner.
+/// - Moves throwing away a win for a draw are **mistakes**
+/// - Moves turning a win or a draw into a loss are **blunders**
+///
+/// A move's accuracy measures its score between the worst and best moves available, and each
+/// player's accuracy is the mean accuracy of their moves.
+///
+/// The analyser's solver keeps its transposition table between plies, so positions explored
+/// while analysing one ply speed up the next. Each ply is also solved with a hint from the
+/// previous one, as the score of the move played is the score of the following position.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{GameAnalyser, MoveClassification};
+///
+///  let mut analyser = GameAnalyser::new();
+///
+///  // Both players keep stacking their pieces, until the first player connects four
+///  let report = analyser.analyse("4343434").unwrap();
+///  assert_eq!(report.winner, Some(1));
+///
+///  // The first player throws away their win, and the second player misses the draw
+///  let annotation = &report.annotations[2];
+///  assert_eq!((annotation.classification, annotation.best_moves.clone()), (MoveClassification::Mistake, vec![5]));
+///  let annotation = &report.annotations[3];
+///  assert_eq!((annotation.classification, annotation.best_moves.clone()), (MoveClassification::Blunder, vec![3]));
+///
+///  assert_eq!(report.count(2, MoveClassification::Blunder), 1);
+///  assert!(report.accuracy[0] > report.accuracy[1]);
+///
+///  // Moves after the game has been won are rejected
+///  assert!(analyser.analyse("43434344").is_err());
+/// ```
+#[derive(Debug)]
+pub struct GameAnalyser {
+    /// The solver used to score the moves at each ply.
+    pub solver: Solver,
+
+    /// The largest score loss for which a move keeping the best outcome is classified as
+    /// good rather than an inaccuracy.
+    pub good_score_loss: i8,
+}
+
+impl GameAnalyser {
+    /// Creates a new game analyser with a default solver.
+    pub fn new() -> GameAnalyser {
+        Self::with_solver(Solver::new())
+    }
+
+    /// Creates a new game analyser which scores moves with the given solver.
+    ///
+    /// If the solver's `parallel_move_scores` field is set, the possible moves at each ply
+    /// are solved concurrently.
+    pub fn with_solver(solver: Solver) -> GameAnalyser {
+        GameAnalyser { solver, good_score_loss: 2 }
+    }
+
+    /// Analyses a game given as a sequence of 1-based columns, returning a report annotating
+    /// each of its moves.
+    ///
+    /// The sequence may be a complete game, ending with a winning move or a full board, or
+    /// an unfinished one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PositionParsingError` if the sequence contains an invalid move, including
+    /// any move made after the game has been won.
+    pub fn analyse(&mut self, moves: &str) -> Result<GameReport, PositionParsingError> {
+        let columns = Self::parse_game(moves)?;
+
+        let mut position = Position::new();
+        let mut annotations = Vec::with_capacity(columns.len());
+        let mut accuracy_sums = [0.0; 2];
+        let mut move_counts = [0; 2];
+        let mut winner = None;
+        let mut expected_score = None;
+
+        for (ply, &column) in columns.iter().enumerate() {
+            let scores = self.move_scores(&position, expected_score);
+            let annotation = self.annotate(ply, column, &scores);
+            let player_index = ply % 2;
+            accuracy_sums[player_index] += annotation.accuracy;
+            move_counts[player_index] += 1;
+            expected_score = Some(-annotation.score);
+            annotations.push(annotation);
+
+            if position.is_winning_move(column) {
+                winner = Some(player_index as u8 + 1);
+            }
+            position.play(column);
+        }
+
+        let accuracy = [0, 1].map(|i| {
+            if move_counts[i] == 0 { 100.0 } else { accuracy_sums[i] / move_counts[i] as f64 }
+        });
+
+        Ok(GameReport { moves: moves.to_string(), annotations, accuracy, winner })
+    }
+
+    /// Scores the possible moves in a position, hinting the search with its expected score
+    /// if one is known.
+    fn move_scores(&mut self, position: &Position, expected_score: Option<i8>) -> [Option<i8>; Position::WIDTH] {
+        match (expected_score, self.solver.parallel_move_scores) {
+            (Some(expected), true) => self.solver.get_all_move_scores_parallel_with_hint(position, expected),
+            (Some(expected), false) => self.solver.get_all_move_scores_with_hint(position, expected),
+            (None, true) => self.solver.get_all_move_scores_parallel(position),
+            (None, false) => self.solver.get_all_move_scores(position),
+        }
+    }
+
+    /// Annotates a move given the scores of every possible move in its position.
+    fn annotate(&self, ply: usize, column: usize, scores: &[Option<i8>; Position::WIDTH]) -> MoveAnnotation {
+        let score = scores[column].expect("A played move should have a score.");
+        let best_score = scores.iter().flatten().copied().max().unwrap_or(score);
+        let worst_score = scores.iter().flatten().copied().min().unwrap_or(score);
+        let best_moves = Solver::COLUMNS
+            .iter()
+            .copied()
+            .filter(|&column| scores[column] == Some(best_score))
+            .collect();
+
+        let accuracy = if best_score == worst_score {
+            100.0
+        } else {
+            100.0 * (score - worst_score) as f64 / (best_score - worst_score) as f64
+        };
+
+        let classification = if score == best_score {
+            MoveClassification::Best
+        } else if Outcome::from_score(score) == Outcome::from_score(best_score) {
+            if best_score - score <= self.good_score_loss {
+                MoveClassification::Good
+            } else {
+                MoveClassification::Inaccuracy
+            }
+        } else if Outcome::from_score(score) == Outcome::Loss {
+            MoveClassification::Blunder
+        } else {
+            MoveClassification::Mistake
+        };
+
+        MoveAnnotation {
+            ply,
+            player: (ply % 2) as u8 + 1,
+            column,
+            score,
+            best_score,
+            best_moves,
+            accuracy,
+            classification,
+        }
+    }
+
+    /// Parses a game's move sequence into 0-based columns, validating each move in the same
+    /// way as `Position::from_moves`, except that the final move may win the game.
+    fn parse_game(moves: &str) -> Result<Vec<usize>, PositionParsingError> {
+        let mut position = Position::new();
+        let mut columns = Vec::with_capacity(moves.len());
+        let mut winning_index = None;
+
+        for (i, c) in moves.chars().enumerate() {
+            // Any move after a winning move is invalid, so the winning move is reported
+            if let Some(index) = winning_index {
+                let column = columns[index] + 1;
+                return Err(PositionParsingError::InvalidWinningMove { column, index });
+            }
+
+            let column = match c.to_digit(10).map(|digit| digit as usize) {
+                Some(column @ 1..=Position::WIDTH) => column - 1,
+                Some(column) => return Err(PositionParsingError::InvalidColumn { column, index: i }),
+                None => return Err(PositionParsingError::InvalidCharacter { character: c, index: i }),
+            };
+            if !position.is_playable(column) {
+                return Err(PositionParsingError::InvalidFullColumnMove { column: column + 1, index: i });
+            }
+            if position.is_winning_move(column) {
+                winning_index = Some(i);
+            }
+
+            position.play(column);
+            columns.push(column);
+        }
+
+        Ok(columns)
+    }
+}
+
+/// Default constructor for the `GameAnalyser` struct.
+impl Default for GameAnalyser {
+    fn default() -> GameAnalyser {
+        GameAnalyser::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,206 @@
+//! Provides a catalogue of handicaps, which give a human player odds against an AI player by
+//! changing which player moves first or restricting the AI's opening move.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use super::move_filter::allowed_moves;
+use crate::{ForbiddenColumns, Outcome, OutcomeProver, Position, Solver};
+
+/// A handicap for a game between a human and an AI player.
+///
+/// Each handicap decides the game's starting position, which player moves first, and any
+/// restriction on the AI's opening move. A game without a handicap starts from the empty
+/// board with the AI moving first, which the AI is guaranteed to win with perfect play.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Handicap, Outcome, Solver};
+///
+///  let mut solver = Solver::new();
+///  assert_eq!(Handicap::None.outcome(&mut solver), Ok(Outcome::Loss));
+///  assert_eq!(Handicap::EdgeOpening.outcome(&mut solver), Ok(Outcome::Win));
+///
+///  // The human's opening disc is already played, so the AI moves next
+///  let pos = Handicap::HumanOpening(3).position().unwrap();
+///  assert_eq!(pos.get_moves(), 1);
+///  assert!(!Handicap::HumanOpening(3).human_moves_first());
+///  assert_eq!(Handicap::HumanOpening(3).outcome(&mut solver), Ok(Outcome::Win));
+///
+///  // Openings in the edge columns lose for the human
+///  assert_eq!(Handicap::HumanOpening(0).outcome(&mut solver), Ok(Outcome::Loss));
+///  assert!(Handicap::HumanOpening(7).position().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Handicap {
+    /// The standard game, in which the AI moves first.
+    #[default]
+    None,
+    /// The standard game, in which the human moves first.
+    AIMovesSecond,
+    /// The AI moves first, but must open in one of the edge columns.
+    EdgeOpening,
+    /// The human moves first, with their opening disc played in the given 0-based column,
+    /// after which the AI moves. This is the same game as `AIMovesSecond` with a fixed
+    /// opening, so it is not part of the catalogue in `ALL`.
+    HumanOpening(usize),
+}
+
+impl Handicap {
+    /// The catalogue of handicaps, from no handicap to the strongest.
+    pub const ALL: [Handicap; 3] = [
+        Handicap::None,
+        Handicap::EdgeOpening,
+        Handicap::AIMovesSecond,
+    ];
+
+    /// Indicates whether the human player makes the first move of the game.
+    pub fn human_moves_first(&self) -> bool {
+        match self {
+            Handicap::None | Handicap::EdgeOpening | Handicap::HumanOpening(_) => false,
+            Handicap::AIMovesSecond => true,
+        }
+    }
+
+    /// Returns the filter restricting the AI's moves, if the handicap has one.
+    ///
+    /// Handicaps only restrict the AI's opening move, so the positions after it can be
+    /// solved without a filter.
+    pub fn move_filter(&self) -> Option<ForbiddenColumns> {
+        match self {
+            Handicap::EdgeOpening => Some(ForbiddenColumns::new(&[1, 2, 3, 4, 5]).for_player(1).for_moves(1)),
+            _ => None,
+        }
+    }
+
+    /// Checks that the handicap describes a legal starting position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HandicapError` if the human's opening disc is played outside the board.
+    pub fn validate(&self) -> Result<(), HandicapError> {
+        self.position().map(|_| ())
+    }
+
+    /// Returns the starting position of a game with the handicap.
+    ///
+    /// The position's current player is the human if `human_moves_first` is true, and the AI
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HandicapError` if the starting position is not legal.
+    pub fn position(&self) -> Result<Position, HandicapError> {
+        let mut position = Position::new();
+        if let Handicap::HumanOpening(column) = *self {
+            if column >= Position::WIDTH {
+                return Err(HandicapError::InvalidColumn { column });
+            }
+            position.play(column);
+        }
+        Ok(position)
+    }
+
+    /// Finds the theoretical outcome of a game with the handicap from the human's
+    /// perspective, assuming that both players play perfectly.
+    ///
+    /// Outcomes are proven with null-window searches, which are cheaper than finding the
+    /// exact score of the starting position. The solver should not have a move filter set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HandicapError` if the starting position is not legal.
+    pub fn outcome(&self, solver: &mut Solver) -> Result<Outcome, HandicapError> {
+        let position = self.position()?;
+
+        // Finds the outcome for the first player, checking each allowed opening if restricted
+        let outcome = match self.move_filter() {
+            Some(filter) => {
+                let allowed = allowed_moves(&filter, &position);
+                (0..Position::WIDTH)
+                    .filter(|&column| allowed & Position::column_mask(column) != 0)
+                    .map(|column| {
+                        let mut child = position;
+                        child.play(column);
+                        opposite(prove_outcome(solver, &child))
+                    })
+                    .min_by_key(|&outcome| outcome_rank(outcome))
+                    .expect("The starting position should have an allowed move.")
+            }
+            None => prove_outcome(solver, &position),
+        };
+
+        Ok(if self.human_moves_first() { outcome } else { opposite(outcome) })
+    }
+
+    /// Removes the scores of the moves which the handicap forbids in a position.
+    pub(crate) fn restrict_move_scores(&self, position: &Position, scores: &mut [Option<i8>; Position::WIDTH]) {
+        if let Some(filter) = self.move_filter() {
+            let allowed = allowed_moves(&filter, position);
+            for (column, score) in scores.iter_mut().enumerate() {
+                if allowed & Position::column_mask(column) == 0 {
+                    *score = None;
+                }
+            }
+        }
+    }
+}
+
+/// Proves the outcome of a position for its current player.
+fn prove_outcome(solver: &mut Solver, position: &Position) -> Outcome {
+    if solver.is_win(position) {
+        Outcome::Win
+    } else if solver.is_loss(position) {
+        Outcome::Loss
+    } else {
+        Outcome::Draw
+    }
+}
+
+/// Returns the outcome of a game for the other player.
+fn opposite(outcome: Outcome) -> Outcome {
+    match outcome {
+        Outcome::Win => Outcome::Loss,
+        Outcome::Draw => Outcome::Draw,
+        Outcome::Loss => Outcome::Win,
+    }
+}
+
+/// Ranks outcomes from best to worst, for the player they belong to.
+fn outcome_rank(outcome: Outcome) -> u8 {
+    match outcome {
+        Outcome::Win => 0,
+        Outcome::Draw => 1,
+        Outcome::Loss => 2,
+    }
+}
+
+impl Display for Handicap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Handicap::None => write!(f, "No handicap"),
+            Handicap::AIMovesSecond => write!(f, "AI moves second"),
+            Handicap::EdgeOpening => write!(f, "AI opens in an edge column"),
+            Handicap::HumanOpening(column) => write!(f, "Human opens in column {}", column + 1),
+        }
+    }
+}
+
+/// An enum for errors that can occur when creating a handicap's starting position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandicapError {
+    /// The human's opening disc is played in an invalid, out of range column.
+    InvalidColumn { column: usize },
+}
+
+impl Display for HandicapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandicapError::InvalidColumn { column } => {
+                write!(f, "invalid column {} for the human's opening", column + 1)
+            }
+        }
+    }
+}
+
+impl Error for HandicapError {}
\ No newline at end of file
@@ -0,0 +1,283 @@
+//! Provides a depth-limited alpha-beta search with a static evaluation of positions.
+
+use crate::{Difficulty, Engine, Position, Solver};
+use super::search_engine::best_scored_move;
+
+/// The value of a proven result in the heuristic search, above which the remainder is the
+/// result's exact score. Static evaluations always lie strictly between `-PROVEN` and `PROVEN`.
+const PROVEN: i32 = 1 << 20;
+
+/// The largest magnitude of a static evaluation.
+const MAX_EVALUATION: i32 = 250;
+
+/// The weight of each column for centre control, as pieces closer to the centre take part in
+/// more possible 4-alignments.
+const COLUMN_WEIGHTS: [i32; Position::WIDTH] = [1, 2, 3, 4, 3, 2, 1];
+
+/// The value of each threat, which is an empty cell completing a 4-alignment for its player.
+const THREAT_WEIGHT: i32 = 12;
+
+/// The extra value of a threat on a row of the parity which favours its player.
+const PARITY_WEIGHT: i32 = 24;
+
+/// The value of each winning spot created by the current player's strongest move.
+const INITIATIVE_WEIGHT: i32 = 4;
+
+/// A mask of the odd rows of the board (the 1st, 3rd and 5th from the bottom), where threats
+/// favour the first player. Threats on the remaining even rows favour the second player.
+const ODD_ROWS_MASK: u64 = const {
+    let mut mask = 0;
+    let mut column = 0;
+    while column < Position::WIDTH {
+        let mut row = 0;
+        while row < Position::HEIGHT {
+            mask |= 1 << (row + column * (Position::HEIGHT + 1));
+            row += 2;
+        }
+        column += 1;
+    }
+    mask
+};
+
+/// A depth-limited engine, which estimates scores using an alpha-beta search with a static
+/// evaluation of the positions at its horizon.
+///
+/// Unlike the `Solver`, this engine doesn't need to search to the end of the game, so its
+/// strength can be adjusted by its search depth, and it remains fast at any stage of the game.
+/// This makes it suitable for human-like opponents, and as a fallback for positions which
+/// can't be solved perfectly in the time available.
+///
+/// The static evaluation is built on the players' threats from `Position`'s winning position
+/// masks, and considers:
+/// - Odd and even threats: the first player benefits most from threats on odd rows, and the
+///   second player from threats on even rows, as these tend to be claimed in the endgame
+/// - Centre control, weighted by the number of 4-alignments each column takes part in
+/// - The initiative, from the `score_move` count of the current player's strongest move
+///
+/// Wins and losses found within the search horizon are given their exact scores. Estimated
+/// scores are scaled to lie strictly between the fastest proven loss and win which the search
+/// could find, so that proven results always rank above and below estimates.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Engine, HeuristicEngine, Position, Solver};
+///
+///  let mut engine = HeuristicEngine::new(4);
+///
+///  // The engine finds the forced win in this position within its horizon
+///  let pos = Position::from_moves("444343533654").unwrap();
+///  assert_eq!(engine.evaluate(&pos), Solver::new().solve(&pos));
+///  assert_eq!(engine.best_move(&pos), Some(4));
+///
+///  // When the search reaches the end of the game, the scores are exact
+///  let pos = Position::from_moves("7422341735647741166133573473242566").unwrap();
+///  let mut engine = HeuristicEngine::new(8);
+///  assert_eq!(engine.move_scores(&pos), Solver::new().get_all_move_scores(&pos));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeuristicEngine {
+    /// The number of moves searched ahead before positions are statically evaluated.
+    pub depth: u8,
+
+    /// A counter for the number of nodes explored in the last search.
+    pub explored_positions: usize,
+}
+
+impl HeuristicEngine {
+    /// Creates a new heuristic engine which searches the given number of moves ahead.
+    pub fn new(depth: u8) -> HeuristicEngine {
+        HeuristicEngine { depth: depth.max(1), explored_positions: 0 }
+    }
+
+    /// Creates a new heuristic engine whose search depth suits the given difficulty.
+    ///
+    /// Combined with the difficulty's move selection in an `AIPlayer`, this gives opponents
+    /// which make human-like mistakes, missing tactics beyond their horizon.
+    pub fn with_difficulty(difficulty: Difficulty) -> HeuristicEngine {
+        Self::new(Self::difficulty_depth(difficulty))
+    }
+
+    /// Returns the search depth used for the given difficulty.
+    pub fn difficulty_depth(difficulty: Difficulty) -> u8 {
+        match difficulty {
+            Difficulty::Easy => 2,
+            Difficulty::Medium => 4,
+            Difficulty::Hard => 6,
+            Difficulty::Impossible => 10,
+        }
+    }
+
+    /// Searches each possible move in a position, returning their values from the current
+    /// player's perspective.
+    fn move_values(&mut self, position: &Position) -> [Option<i32>; Position::WIDTH] {
+        self.explored_positions = 0;
+        let mut values = [None; Position::WIDTH];
+
+        // If the game is won or the position is full, no moves are possible
+        if position.is_won_position() || position.get_moves() == Position::BOARD_SIZE {
+            return values;
+        }
+
+        for (column, value) in values.iter_mut().enumerate() {
+            if !position.is_playable(column) {
+                continue;
+            }
+            if position.is_winning_move(column) {
+                *value = Some(PROVEN + (Position::BOARD_SIZE + 1 - position.get_moves()) as i32 / 2);
+            } else {
+                let mut child = *position;
+                child.play(column);
+                *value = Some(-self.negamax(&child, self.depth - 1, -PROVEN * 2, PROVEN * 2));
+            }
+        }
+
+        values
+    }
+
+    /// Converts a search value into a score for a position with the given number of moves,
+    /// keeping the exact scores of proven results and scaling estimates to fit below them.
+    fn to_score(&self, value: i32, moves: usize) -> i8 {
+        if value.abs() > PROVEN {
+            return (value.signum() * (value.abs() - PROVEN)) as i8;
+        }
+
+        // The slowest result the search could prove is a loss to the second piece played after
+        // its horizon, which is found when no safe moves remain at the horizon
+        let horizon = (moves + self.depth as usize + 2) as i32;
+        let limit = ((Position::BOARD_SIZE as i32 + 2 - horizon) / 2 - 1).max(0);
+        let scaled = (value * limit + value.signum() * MAX_EVALUATION / 2) / MAX_EVALUATION;
+        scaled.clamp(-limit, limit) as i8
+    }
+
+    /// The depth-limited negamax search with alpha-beta pruning.
+    fn negamax(&mut self, position: &Position, depth: u8, mut alpha: i32, beta: i32) -> i32 {
+        self.explored_positions += 1;
+        let moves = position.get_moves();
+
+        // Checks for a drawn game
+        if moves == Position::BOARD_SIZE {
+            return 0;
+        }
+
+        // The current player wins with their next move
+        if position.can_win_next() {
+            return PROVEN + (Position::BOARD_SIZE + 1 - moves) as i32 / 2;
+        }
+
+        // If there are no possible non-losing moves, then the opponent is guaranteed to win
+        let possible_moves = position.possible_non_losing_moves();
+        if possible_moves == 0 {
+            return -(PROVEN + (Position::BOARD_SIZE - moves) as i32 / 2);
+        }
+
+        if depth == 0 {
+            return Self::evaluate_statically(position);
+        }
+
+        // Explores the moves creating the most winning spots first, breaking ties by centrality
+        let mut sorted_moves = [(0, 0); Position::WIDTH];
+        let mut move_count = 0;
+        for &column in Solver::COLUMNS.iter() {
+            let move_bit = possible_moves & Position::column_mask(column);
+            if move_bit > 0 {
+                sorted_moves[move_count] = (column, position.score_move(move_bit));
+                move_count += 1;
+            }
+        }
+        let sorted_moves = &mut sorted_moves[..move_count];
+        sorted_moves.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+        let mut best = -PROVEN * 2;
+        for &(column, _) in sorted_moves.iter() {
+            let mut child = *position;
+            child.play(column);
+            let value = -self.negamax(&child, depth - 1, -beta, -alpha);
+            best = best.max(value);
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Statically evaluates a position from the current player's perspective, returning a value
+    /// between `-MAX_EVALUATION` and `MAX_EVALUATION`.
+    ///
+    /// Assumes that neither player can win with their next move.
+    fn evaluate_statically(position: &Position) -> i32 {
+        let current = position.position;
+        let opponent = position.position ^ position.mask;
+
+        // Centre control
+        let mut value = 0;
+        for (column, &weight) in COLUMN_WEIGHTS.iter().enumerate() {
+            let column_mask = Position::column_mask(column);
+            let difference = (current & column_mask).count_ones() as i32 - (opponent & column_mask).count_ones() as i32;
+            value += weight * difference;
+        }
+
+        // Odd and even threats, where the first player is the current player if the number of
+        // moves played is even
+        let current_is_first = position.get_moves().is_multiple_of(2);
+        let (current_rows, opponent_rows) = if current_is_first {
+            (ODD_ROWS_MASK, !ODD_ROWS_MASK)
+        } else {
+            (!ODD_ROWS_MASK, ODD_ROWS_MASK)
+        };
+        let current_threats = position.winning_positions();
+        let opponent_threats = position.opponent_winning_positions();
+        value += THREAT_WEIGHT * (current_threats.count_ones() as i32 - opponent_threats.count_ones() as i32);
+        value += PARITY_WEIGHT * ((current_threats & current_rows).count_ones() as i32
+            - (opponent_threats & opponent_rows).count_ones() as i32);
+
+        // The initiative, from the current player's strongest safe move
+        let possible_moves = position.possible_non_losing_moves();
+        let initiative = (0..Position::WIDTH)
+            .map(|column| possible_moves & Position::column_mask(column))
+            .filter(|&move_bit| move_bit > 0)
+            .map(|move_bit| position.score_move(move_bit) as i32)
+            .max()
+            .unwrap_or(0);
+        value += INITIATIVE_WEIGHT * initiative;
+
+        value.clamp(-MAX_EVALUATION, MAX_EVALUATION)
+    }
+}
+
+impl Engine for HeuristicEngine {
+    fn name(&self) -> &str {
+        "Heuristic"
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        let value = self.move_values(position)
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(0);
+        self.to_score(value, position.get_moves())
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.move_values(position).map(|value| value.map(|value| self.to_score(value, position.get_moves())))
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        // Uses the unscaled values, so that estimates which round to the same score are ranked
+        best_scored_move(&self.move_values(position))
+    }
+
+    fn reset(&mut self) {
+        self.explored_positions = 0;
+    }
+}
+
+/// Default constructor for the `HeuristicEngine` struct.
+impl Default for HeuristicEngine {
+    fn default() -> HeuristicEngine {
+        HeuristicEngine::new(8)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,469 @@
+//! Provides a Monte Carlo Tree Search engine, which estimates the value of moves by playing out
+//! many games from the current position.
+
+use web_time::Instant;
+use rand::rngs::StdRng;
+use rand::{rng, Rng, SeedableRng};
+use rayon::prelude::*;
+use crate::{Engine, Position, SearchLimits};
+use super::search_engine::best_scored_move;
+
+/// The policy used to select moves in the playouts of a Monte Carlo Tree Search.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PlayoutPolicy {
+    /// Plays uniformly random moves until the game ends.
+    Random,
+
+    /// Plays a winning move whenever one exists, and otherwise plays uniformly random moves from
+    /// `Position::possible_non_losing_moves`. The same moves are used to expand the search tree.
+    NonLosing,
+}
+
+/// The statistics of a node in a search tree.
+#[derive(Debug)]
+struct Node {
+    /// The position at the node.
+    position: Position,
+
+    /// The column played to reach the node from its parent.
+    column: usize,
+
+    /// The indices of the node's expanded children.
+    children: Vec<usize>,
+
+    /// A mask of the moves from the node which haven't been expanded yet.
+    untried_moves: u64,
+
+    /// The number of iterations which have passed through the node.
+    visits: u32,
+
+    /// The total reward of those iterations, from the perspective of the player who moved into
+    /// the node.
+    reward: f64,
+
+    /// The reward for the player who moved into the node, if the node ends the game.
+    terminal: Option<f64>,
+}
+
+/// A single search tree, which is grown by one node in each iteration.
+struct SearchTree {
+    nodes: Vec<Node>,
+    policy: PlayoutPolicy,
+    exploration: f64,
+    rng: StdRng,
+}
+
+impl SearchTree {
+    /// Creates a new search tree rooted at the given position.
+    fn new(position: &Position, policy: PlayoutPolicy, exploration: f64, seed: u64) -> SearchTree {
+        let mut tree = SearchTree { nodes: Vec::new(), policy, exploration, rng: StdRng::seed_from_u64(seed) };
+        let untried_moves = tree.tree_moves(position);
+        tree.nodes.push(Node { position: *position, column: 0, children: Vec::new(), untried_moves, visits: 0, reward: 0., terminal: None });
+        tree
+    }
+
+    /// Returns a mask of the moves from a position which are explored by the tree.
+    fn tree_moves(&self, position: &Position) -> u64 {
+        match self.policy {
+            PlayoutPolicy::Random => position.possible(),
+            PlayoutPolicy::NonLosing => position.possible_non_losing_moves(),
+        }
+    }
+
+    /// Adds a node to the tree, returning its index.
+    fn add_node(&mut self, position: Position, column: usize, terminal: Option<f64>) -> usize {
+        let mut terminal = terminal;
+        if terminal.is_none() && self.policy == PlayoutPolicy::NonLosing {
+            // The player to move wins next, or the player who moved into the node will
+            if position.can_win_next() {
+                terminal = Some(-1.);
+            } else if position.possible_non_losing_moves() == 0 {
+                terminal = Some(1.);
+            }
+        }
+
+        let untried_moves = match terminal {
+            Some(_) => 0,
+            None => self.tree_moves(&position),
+        };
+
+        self.nodes.push(Node { position, column, children: Vec::new(), untried_moves, visits: 0, reward: 0., terminal });
+        self.nodes.len() - 1
+    }
+
+    /// Runs a single iteration of the search: selecting a path through the tree, expanding it by
+    /// one node, playing out a game from that node and propagating the result back up the path.
+    fn iterate(&mut self) {
+        let mut path = vec![0];
+        let mut index = 0;
+
+        // Selects children by their upper confidence bounds until reaching an unexpanded node
+        while self.nodes[index].terminal.is_none()
+            && self.nodes[index].untried_moves == 0
+            && !self.nodes[index].children.is_empty()
+        {
+            index = self.select_child(index);
+            path.push(index);
+        }
+
+        // Expands one of the node's untried moves, and plays out a game from the new node
+        let mut reward = match self.nodes[index].terminal {
+            Some(reward) => reward,
+            None if self.nodes[index].untried_moves == 0 => 0.,
+            None => {
+                let move_bit = self.random_move(self.nodes[index].untried_moves);
+                self.nodes[index].untried_moves &= !move_bit;
+                let child = self.expand(index, move_bit);
+                path.push(child);
+                match self.nodes[child].terminal {
+                    Some(reward) => reward,
+                    None => -self.playout(self.nodes[child].position),
+                }
+            }
+        };
+
+        // Propagates the reward, alternating its perspective at each level
+        for &index in path.iter().rev() {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.reward += reward;
+            reward = -reward;
+        }
+    }
+
+    /// Selects the child of a node with the highest upper confidence bound (UCT).
+    fn select_child(&self, index: usize) -> usize {
+        let log_visits = (self.nodes[index].visits as f64).ln();
+        let bound = |child: usize| {
+            let node = &self.nodes[child];
+            let visits = node.visits as f64;
+            node.reward / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+
+        self.nodes[index].children
+            .iter()
+            .copied()
+            .reduce(|best, child| if bound(child) > bound(best) { child } else { best })
+            .expect("A fully expanded node should have at least one child.")
+    }
+
+    /// Adds the child reached by playing the given move from a node, returning its index.
+    fn expand(&mut self, index: usize, move_bit: u64) -> usize {
+        let parent = self.nodes[index].position;
+        let column = Self::move_column(move_bit);
+
+        let terminal = if parent.is_winning_move(column) {
+            Some(1.)
+        } else if parent.get_moves() + 1 == Position::BOARD_SIZE {
+            Some(0.)
+        } else {
+            None
+        };
+
+        let mut position = parent;
+        position.play(column);
+        let child = self.add_node(position, column, terminal);
+        self.nodes[index].children.push(child);
+        child
+    }
+
+    /// Plays out a game from the given position using the tree's playout policy, returning the
+    /// reward for the current player.
+    fn playout(&mut self, mut position: Position) -> f64 {
+        let mut sign = 1.;
+        loop {
+            if position.get_moves() == Position::BOARD_SIZE {
+                return 0.;
+            }
+
+            let moves = match self.policy {
+                PlayoutPolicy::Random => position.possible(),
+                PlayoutPolicy::NonLosing => {
+                    if position.can_win_next() {
+                        return sign;
+                    }
+                    position.possible_non_losing_moves()
+                }
+            };
+
+            // With no non-losing moves, the opponent is guaranteed to win
+            if moves == 0 {
+                return -sign;
+            }
+
+            let column = Self::move_column(self.random_move(moves));
+            if position.is_winning_move(column) {
+                return sign;
+            }
+            position.play(column);
+            sign = -sign;
+        }
+    }
+
+    /// Returns a uniformly random move bit from a non-empty mask of moves.
+    fn random_move(&mut self, moves: u64) -> u64 {
+        let mut moves = moves;
+        for _ in 0..self.rng.random_range(0..moves.count_ones()) {
+            moves &= moves - 1;
+        }
+        moves & moves.wrapping_neg()
+    }
+
+    /// Returns the column of a move bit.
+    fn move_column(move_bit: u64) -> usize {
+        move_bit.trailing_zeros() as usize / (Position::HEIGHT + 1)
+    }
+
+    /// Returns the number of visits and total reward of each of the root's moves.
+    fn root_statistics(&self) -> [Option<(u32, f64)>; Position::WIDTH] {
+        let mut statistics = [None; Position::WIDTH];
+        for &child in self.nodes[0].children.iter() {
+            let node = &self.nodes[child];
+            statistics[node.column] = Some((node.visits, node.reward));
+        }
+        statistics
+    }
+}
+
+/// An engine which estimates the value of moves using Monte Carlo Tree Search with the UCT
+/// selection rule.
+///
+/// Each search grows a tree from the current position, choosing which moves to explore by
+/// balancing their average playout results against how rarely they have been tried. The
+/// playouts can be uniformly random, or guided by `Position::possible_non_losing_moves` for a
+/// much stronger engine. Unlike the `Solver`, the search never needs to reach the end of every
+/// line, so it gives a strong but imperfect opponent at any stage of the game, which is useful
+/// as a contrast to the perfect solver.
+///
+/// The search runs until its limits are reached: `max_nodes` gives the number of iterations
+/// for each search tree, and `max_time` the time available. If no limits are set, a default
+/// number of iterations is used.
+///
+/// With more than one thread, an independent tree is searched on each thread using `rayon`,
+/// and the statistics of their root moves are combined (root parallelisation).
+///
+/// # Scores
+///
+/// Immediately winning moves, and moves which allow the opponent to win immediately, are given
+/// their exact scores. Other moves are given their average playout result, scaled to lie
+/// strictly between these, so the engine's scores can be used by an `AIPlayer`. Moves which
+/// the search didn't visit, such as in very short searches, are given a neutral score of 0. The engine's
+/// best move is the most visited move rather than the highest scoring one, as is usual for MCTS.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Engine, MctsEngine, Position, SearchLimits};
+///
+///  let mut engine = MctsEngine::new(SearchLimits::new().with_max_nodes(20_000));
+///  engine.seed = Some(0);
+///
+///  // The engine finds a move which creates two threats at once
+///  let pos = Position::from_moves("4455").unwrap();
+///  assert!(matches!(engine.best_move(&pos), Some(2 | 5)));
+///
+///  // Immediately winning moves are given their exact scores
+///  let pos = Position::from_moves("445566").unwrap();
+///  assert_eq!(engine.move_scores(&pos)[2], Some(18));
+///
+///  // After a single iteration, only moves allowing the opponent to win are given losing scores
+///  let mut engine = MctsEngine::new(SearchLimits::new().with_max_nodes(1));
+///  engine.seed = Some(0);
+///  let pos = Position::from_moves("11223").unwrap();
+///  let scores = engine.move_scores(&pos);
+///  assert!(scores[3].is_some_and(|score| score > -18));
+///  for column in [0, 1, 2, 4, 5, 6] {
+///      assert_eq!(scores[column], Some(-18));
+///  }
+///  let pos = Position::from_moves("4455").unwrap();
+///  assert!(engine.move_scores(&pos).iter().all(|score| score.is_some_and(|score| score > -19)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MctsEngine {
+    /// Limits on the iterations and time used by each search.
+    pub limits: SearchLimits,
+
+    /// The policy used to select moves in playouts.
+    pub playout_policy: PlayoutPolicy,
+
+    /// The exploration constant of the UCT rule, which is larger to try less promising moves
+    /// more often.
+    pub exploration: f64,
+
+    /// The number of independent search trees, each searched on its own thread.
+    pub threads: usize,
+
+    /// The seed for the random number generator used in playouts, or `None` to use a
+    /// different seed for each search.
+    pub seed: Option<u64>,
+
+    /// A counter for the number of iterations run in the last search, across all threads.
+    pub explored_positions: usize,
+}
+
+impl MctsEngine {
+    /// The number of iterations for each search tree when no limits are set.
+    pub const DEFAULT_ITERATIONS: usize = 100_000;
+
+    /// The number of iterations between checks of the search's time limit.
+    const TIME_CHECK_INTERVAL: usize = 1 << 6;
+
+    /// Creates a new engine which searches until the given limits are reached, using
+    /// non-losing playouts on a single thread.
+    pub fn new(limits: SearchLimits) -> MctsEngine {
+        MctsEngine {
+            limits,
+            playout_policy: PlayoutPolicy::NonLosing,
+            exploration: std::f64::consts::SQRT_2,
+            threads: 1,
+            seed: None,
+            explored_positions: 0,
+        }
+    }
+
+    /// Searches the given position, returning the number of visits and average reward of each
+    /// of its moves which the search tree explored.
+    fn search(&mut self, position: &Position) -> [Option<(u32, f64)>; Position::WIDTH] {
+        let limits = if self.limits.is_unlimited() {
+            SearchLimits::new().with_max_nodes(Self::DEFAULT_ITERATIONS)
+        } else {
+            self.limits
+        };
+        let deadline = limits.max_time.map(|max_time| Instant::now() + max_time);
+        let seed = self.seed.unwrap_or_else(|| rng().random());
+        let (policy, exploration) = (self.playout_policy, self.exploration);
+
+        let search_tree = |thread: usize| {
+            let mut tree = SearchTree::new(position, policy, exploration, seed.wrapping_add(thread as u64));
+            let mut iterations = 0;
+            while limits.max_nodes.is_none_or(|max_nodes| iterations < max_nodes) {
+                if iterations % Self::TIME_CHECK_INTERVAL == 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    break;
+                }
+                tree.iterate();
+                iterations += 1;
+            }
+            (tree.root_statistics(), iterations)
+        };
+
+        let results: Vec<_> = if self.threads > 1 {
+            (0..self.threads).into_par_iter().map(search_tree).collect()
+        } else {
+            vec![search_tree(0)]
+        };
+
+        // Combines the root statistics of each tree
+        let mut statistics: [Option<(u32, f64)>; Position::WIDTH] = [None; Position::WIDTH];
+        self.explored_positions = 0;
+        for (tree_statistics, iterations) in results {
+            self.explored_positions += iterations;
+            for (combined, tree) in statistics.iter_mut().zip(tree_statistics) {
+                if let Some((visits, reward)) = tree {
+                    let (total_visits, total_reward) = combined.get_or_insert((0, 0.));
+                    *total_visits += visits;
+                    *total_reward += reward;
+                }
+            }
+        }
+
+        statistics.map(|moves| moves.map(|(visits, reward)| (visits, if visits > 0 { reward / visits as f64 } else { 0. })))
+    }
+
+    /// Returns a move which wins immediately in the given position, if one exists.
+    fn winning_move(position: &Position) -> Option<usize> {
+        (0..Position::WIDTH).find(|&column| position.is_playable(column) && position.is_winning_move(column))
+    }
+
+    /// Returns the most visited move in a search of the given position, or any possible move if
+    /// the search found that every move loses.
+    fn most_visited_move(position: &Position, statistics: &[Option<(u32, f64)>; Position::WIDTH]) -> Option<usize> {
+        Self::winning_move(position)
+            .or_else(|| best_scored_move(&statistics.map(|moves| moves.map(|(visits, _)| visits))))
+            .or_else(|| (0..Position::WIDTH).find(|&column| position.is_playable(column)))
+    }
+
+    /// Converts search statistics into scores for each possible move in a position.
+    fn to_scores(position: &Position, statistics: &[Option<(u32, f64)>; Position::WIDTH]) -> [Option<i8>; Position::WIDTH] {
+        let moves = position.get_moves();
+        let win_score = (Position::BOARD_SIZE + 1 - moves) as i8 / 2;
+        let loss_score = -((Position::BOARD_SIZE - moves) as i8 / 2);
+        let limit = (win_score - 1).min(-loss_score - 1).max(0) as f64;
+        let non_losing_moves = position.possible_non_losing_moves();
+
+        let mut scores = [None; Position::WIDTH];
+        for (column, score) in scores.iter_mut().enumerate() {
+            if !position.is_playable(column) {
+                continue;
+            }
+            *score = Some(if position.is_winning_move(column) {
+                win_score
+            } else {
+                match statistics[column] {
+                    Some((_, value)) => (value * limit).round() as i8,
+                    // Moves excluded by non-losing playouts allow the opponent to win next
+                    None if non_losing_moves & Position::column_mask(column) == 0 => loss_score,
+                    // Other moves may be left unvisited by short searches, so nothing is known
+                    None => 0,
+                }
+            });
+        }
+        scores
+    }
+}
+
+impl Engine for MctsEngine {
+    fn name(&self) -> &str {
+        "MCTS"
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        // If the game is won or the position is full, no moves are possible
+        if position.is_won_position() || position.get_moves() == Position::BOARD_SIZE {
+            return 0;
+        }
+
+        // The current player wins with their next move
+        if position.can_win_next() {
+            return (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2;
+        }
+
+        let statistics = self.search(position);
+        Self::most_visited_move(position, &statistics)
+            .and_then(|column| Self::to_scores(position, &statistics)[column])
+            .unwrap_or(0)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        // If the game is won or the position is full, no moves are possible
+        if position.is_won_position() || position.get_moves() == Position::BOARD_SIZE {
+            return [None; Position::WIDTH];
+        }
+        Self::to_scores(position, &self.search(position))
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        // If the game is won or the position is full, no moves are possible
+        if position.is_won_position() || position.get_moves() == Position::BOARD_SIZE {
+            return None;
+        }
+
+        // Plays winning moves without searching
+        if let Some(column) = Self::winning_move(position) {
+            return Some(column);
+        }
+
+        let statistics = self.search(position);
+        Self::most_visited_move(position, &statistics)
+    }
+
+    fn reset(&mut self) {
+        self.explored_positions = 0;
+    }
+}
+
+/// Default constructor for the `MctsEngine` struct.
+impl Default for MctsEngine {
+    fn default() -> MctsEngine {
+        MctsEngine::new(SearchLimits::new())
+    }
+}
\ No newline at end of file
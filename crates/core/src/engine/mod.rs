@@ -2,14 +2,50 @@
 
 mod solver;
 mod transposition_table;
+mod shared_transposition_table;
 mod move_sorter;
+mod move_ordering;
 mod opening_book;
 mod opening_book_generator;
 mod ai_player;
+mod search_statistics;
+mod search_observer;
+mod search_limits;
+mod solver_builder;
+mod outcome_prover;
+mod proof_number_solver;
+mod search_engine;
+mod heuristic_engine;
+mod fallback_engine;
+mod mcts_engine;
+mod rule_evaluator;
+mod move_analysis;
+mod game_analyser;
+mod puzzle_generator;
+mod move_filter;
+mod handicap;
 
 pub use solver::Solver;
-pub use transposition_table::{TranspositionTable, TTEntry, TTFlag};
+pub use transposition_table::{TranspositionTable, TTEntry, TTFlag, TTStore, ReplacementPolicy};
+pub use shared_transposition_table::SharedTranspositionTable;
 pub use move_sorter::{MoveSorter, MoveEntry};
+pub use move_ordering::{MoveOrdering, ThreatOrdering, HistoryOrdering, KillerOrdering, TTMoveFirst};
 pub use opening_book::OpeningBook;
 pub use opening_book_generator::OpeningBookGenerator;
-pub use ai_player::{Difficulty, AIPlayer};
\ No newline at end of file
+pub use ai_player::{Difficulty, AIPlayer};
+pub use search_statistics::SearchStatistics;
+pub use search_observer::{SearchObserver, SearchProgress};
+pub use search_limits::SearchLimits;
+pub use solver_builder::{SolverBuilder, BookSource};
+pub use outcome_prover::OutcomeProver;
+pub use proof_number_solver::ProofNumberSolver;
+pub use search_engine::Engine;
+pub use heuristic_engine::HeuristicEngine;
+pub use fallback_engine::FallbackEngine;
+pub use mcts_engine::{MctsEngine, PlayoutPolicy};
+pub use rule_evaluator::{RuleEvaluator, Rule, RuleApplication, RuleSet, Square};
+pub use move_analysis::{MoveAnalysis, Outcome};
+pub use game_analyser::{GameAnalyser, GameReport, MoveAnnotation, MoveClassification};
+pub use puzzle_generator::{PuzzleGenerator, PuzzleFilter, PuzzleKind, Puzzle};
+pub use move_filter::{MoveFilter, ForbiddenColumns};
+pub use handicap::{Handicap, HandicapError};
\ No newline at end of file
@@ -0,0 +1,59 @@
+//! Provides the results of analysing the moves of a position.
+
+use std::fmt::{Display, Formatter};
+
+/// The outcome of a position or move with perfect play, from the perspective of the player
+/// making the move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    /// Returns the outcome represented by a score.
+    pub fn from_score(score: i8) -> Outcome {
+        match score {
+            s if s > 0 => Outcome::Win,
+            0 => Outcome::Draw,
+            _ => Outcome::Loss,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Outcome::Win => "Winning",
+            Outcome::Draw => "Drawing",
+            Outcome::Loss => "Losing",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The analysis of a single move in a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAnalysis {
+    /// The 0-based column of the move.
+    pub column: usize,
+
+    /// The exact score of the move, from the perspective of the player making it.
+    pub score: i8,
+
+    /// The outcome of the move with perfect play.
+    pub outcome: Outcome,
+
+    /// The 0-based columns of the principal variation, which starts with the move itself and
+    /// continues with perfect play by both players until the end of the game.
+    pub principal_variation: Vec<usize>,
+}
+
+impl MoveAnalysis {
+    /// Returns the principal variation as a string of 1-based columns, which can be appended to
+    /// the move sequence of the analysed position and parsed with `Position::from_moves`.
+    pub fn principal_variation_string(&self) -> String {
+        self.principal_variation.iter().map(|column| (column + 1).to_string()).collect()
+    }
+}
\ No newline at end of file
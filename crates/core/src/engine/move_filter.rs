@@ -0,0 +1,124 @@
+//! Restrictions on the moves which may be played during a solver's search.
+
+use crate::Position;
+use std::fmt::{Debug, Formatter};
+
+/// A restriction on the moves which may be played in each position of a solver's search,
+/// such as a handicap forbidding a player from using some columns.
+///
+/// The restriction may depend on anything determined by the position itself, including the
+/// player to move and the number of moves played, but not on the moves which reached it, as
+/// the solver's transposition table stores one result for each position.
+///
+/// If a filter allows none of the playable moves in a position, the restriction is lifted
+/// for that position and every playable move is allowed, so that the game can continue.
+pub trait MoveFilter: Send + Sync {
+    /// Returns a mask of the cells which the current player is allowed to play in, such as
+    /// a union of `Position::column_mask`s. The mask only needs to cover the playable cells.
+    fn allowed_moves(&self, position: &Position) -> u64;
+
+    /// Indicates whether the filter treats every position in the same way as its mirror
+    /// image, so that the solver can share results between them.
+    ///
+    /// Returning `true` for an asymmetric filter leads to incorrect scores, so filters
+    /// should only override this if they are known to be symmetric.
+    fn is_symmetric(&self) -> bool {
+        false
+    }
+}
+
+/// Allows solvers holding a move filter to be debug formatted.
+impl Debug for dyn MoveFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MoveFilter")
+    }
+}
+
+/// Returns a mask of the moves which may be played in a position under a filter, falling
+/// back to every playable move if the filter allows none of them.
+#[inline(always)]
+pub(crate) fn allowed_moves(filter: &dyn MoveFilter, position: &Position) -> u64 {
+    let possible = position.possible();
+    let allowed = possible & filter.allowed_moves(position);
+    if allowed == 0 { possible } else { allowed }
+}
+
+/// A move filter which forbids playing in a fixed set of columns, optionally only for one
+/// player or for a number of each player's first moves.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{ForbiddenColumns, MoveFilter, Position};
+///
+///  // The first player may not play in the centre column for their first 2 moves
+///  let filter = ForbiddenColumns::new(&[3]).for_player(1).for_moves(2);
+///  let pos = Position::from_moves("12").unwrap();
+///  assert_eq!(filter.allowed_moves(&pos) & Position::column_mask(3), 0);
+///
+///  let pos = Position::from_moves("1212").unwrap();
+///  assert_ne!(filter.allowed_moves(&pos) & Position::column_mask(3), 0);
+///
+///  // Forbidding the centre column treats mirrored positions in the same way
+///  assert!(filter.is_symmetric());
+///  assert!(!ForbiddenColumns::new(&[0]).is_symmetric());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ForbiddenColumns {
+    /// A mask of every cell in the forbidden columns.
+    pub mask: u64,
+
+    /// The player who may not play in the forbidden columns, which is 1 for the first player
+    /// and 2 for the second, or `None` if neither player may.
+    pub player: Option<u8>,
+
+    /// The number of each restricted player's first moves for which the columns are
+    /// forbidden, or `None` if they are forbidden for the whole game.
+    pub moves: Option<usize>,
+}
+
+impl ForbiddenColumns {
+    /// Creates a new filter forbidding both players from playing in the given 0-based columns
+    /// for the whole game.
+    pub fn new(columns: &[usize]) -> ForbiddenColumns {
+        let mask = columns.iter().fold(0, |mask, &column| mask | Position::column_mask(column));
+        ForbiddenColumns { mask, player: None, moves: None }
+    }
+
+    /// Returns a copy of the filter which only restricts the given player, which is 1 for the
+    /// first player and 2 for the second.
+    pub fn for_player(mut self, player: u8) -> ForbiddenColumns {
+        self.player = Some(player);
+        self
+    }
+
+    /// Returns a copy of the filter which only applies to each restricted player's first
+    /// `moves` moves.
+    pub fn for_moves(mut self, moves: usize) -> ForbiddenColumns {
+        self.moves = Some(moves);
+        self
+    }
+
+    /// Indicates whether the restriction applies to the current player of a position.
+    fn applies_to(&self, position: &Position) -> bool {
+        let moves = position.get_moves();
+        let player = (moves % 2) as u8 + 1;
+
+        // The current player has made half of the moves played, rounded down
+        self.player.is_none_or(|restricted| restricted == player)
+            && self.moves.is_none_or(|limit| moves / 2 < limit)
+    }
+}
+
+impl MoveFilter for ForbiddenColumns {
+    fn allowed_moves(&self, position: &Position) -> u64 {
+        if self.applies_to(position) { !self.mask } else { !0 }
+    }
+
+    fn is_symmetric(&self) -> bool {
+        (0..Position::CENTRE).all(|column| {
+            let mirrored = Position::WIDTH - 1 - column;
+            (self.mask & Position::column_mask(column) == 0) == (self.mask & Position::column_mask(mirrored) == 0)
+        })
+    }
+}
\ No newline at end of file
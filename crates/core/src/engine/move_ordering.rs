@@ -0,0 +1,254 @@
+//! Strategies for ordering the moves explored at each node of the solver's search.
+
+use crate::{MoveSorter, Position};
+use std::fmt::{Debug, Formatter};
+
+/// A strategy for ordering the moves explored at each node of the solver's search.
+///
+/// Exploring stronger moves first leads to earlier beta cut-offs, which greatly reduces the
+/// number of nodes the search must explore. Each search thread uses its own copy of the
+/// ordering, created with `clone_box`, so orderings can learn from the cut-offs found during
+/// a search without any synchronisation.
+///
+/// Orderings only change how quickly the search finds each score, never the score itself.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::*;
+///
+///  let orderings: [Box<dyn MoveOrdering>; 4] = [
+///      Box::new(ThreatOrdering),
+///      Box::new(HistoryOrdering::new()),
+///      Box::new(KillerOrdering::new()),
+///      Box::new(TTMoveFirst(ThreatOrdering)),
+///  ];
+///
+///  // Positions from the `end-easy`, `middle-easy` and `middle-medium` test sets
+///  let positions = [
+///      ("2252576253462244111563365343671351441", -1),
+///      ("5554224333234511764415115", 4),
+///      ("274552224131661", 0),
+///      ("5455174361263362", -1),
+///  ];
+///  for ordering in orderings {
+///      let mut solver = Solver::builder()
+///          .transposition_table_memory(16)
+///          .opening_book(BookSource::None)
+///          .build()
+///          .unwrap();
+///      solver.move_ordering = ordering;
+///      for (moves, score) in positions {
+///          solver.reset();
+///          let pos = Position::from_moves(moves).unwrap();
+///          assert_eq!(solver.solve(&pos), score, "wrong score for {moves} with {:?}", solver.move_ordering);
+///      }
+///  }
+/// ```
+pub trait MoveOrdering: Send + Sync {
+    /// Sorts the possible moves in a position, so that the most promising moves are explored
+    /// first.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The position being searched.
+    /// * `possible_moves`: A bitmap of the moves to sort.
+    /// * `columns`: The search thread's column order, used to break ties from first to last.
+    /// * `tt_move`: The best move stored for the position in the transposition table, if any.
+    fn order_moves(
+        &self,
+        position: &Position,
+        possible_moves: u64,
+        columns: &[usize; Position::WIDTH],
+        tt_move: Option<usize>,
+    ) -> MoveSorter;
+
+    /// Called when a move causes a beta cut-off, so that the ordering can learn from it.
+    fn on_cutoff(&mut self, _position: &Position, _column: usize) {}
+
+    /// Creates a boxed copy of the ordering for a new search thread.
+    fn clone_box(&self) -> Box<dyn MoveOrdering>;
+}
+
+/// Allows solvers holding a move ordering to be debug formatted.
+impl Debug for dyn MoveOrdering {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MoveOrdering")
+    }
+}
+
+/// Adds each possible move in a position to a sorter, using the given scoring function.
+///
+/// Columns are added in reverse order, so that ties are broken in favour of earlier columns.
+#[inline(always)]
+fn sort_moves(
+    possible_moves: u64,
+    columns: &[usize; Position::WIDTH],
+    mut score: impl FnMut(usize, u64) -> u32,
+) -> MoveSorter {
+    let mut moves = MoveSorter::new();
+    for &column in columns.iter().rev() {
+        let move_bit = possible_moves & Position::column_mask(column);
+        if move_bit > 0 {
+            moves.add_weighted(column, score(column, move_bit));
+        }
+    }
+    moves
+}
+
+/// The default move ordering, which explores the moves creating the most winning threats
+/// first, breaking ties in favour of columns closer to the centre.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ThreatOrdering;
+
+impl MoveOrdering for ThreatOrdering {
+    fn order_moves(
+        &self,
+        position: &Position,
+        possible_moves: u64,
+        columns: &[usize; Position::WIDTH],
+        _tt_move: Option<usize>,
+    ) -> MoveSorter {
+        sort_moves(possible_moves, columns, |_, move_bit| position.score_move(move_bit) as u32)
+    }
+
+    fn clone_box(&self) -> Box<dyn MoveOrdering> {
+        Box::new(*self)
+    }
+}
+
+/// A move ordering using the history heuristic, which breaks ties between moves creating the
+/// same number of winning threats in favour of moves which have caused the most cut-offs.
+///
+/// Cut-offs are recorded for each player and cell of the board, weighted by the number of
+/// moves remaining in the position, so that cut-offs near the root count for more.
+#[derive(Debug, Clone)]
+pub struct HistoryOrdering {
+    /// The cut-off history for each player and bitboard cell.
+    history: [[u32; 64]; 2],
+}
+
+impl HistoryOrdering {
+    /// Creates a new history ordering with an empty history.
+    pub fn new() -> HistoryOrdering {
+        Self::default()
+    }
+}
+
+impl MoveOrdering for HistoryOrdering {
+    fn order_moves(
+        &self,
+        position: &Position,
+        possible_moves: u64,
+        columns: &[usize; Position::WIDTH],
+        _tt_move: Option<usize>,
+    ) -> MoveSorter {
+        let history = &self.history[position.get_moves() % 2];
+        sort_moves(possible_moves, columns, |_, move_bit| {
+            let threats = position.score_move(move_bit) as u32;
+            threats << 24 | history[move_bit.trailing_zeros() as usize].min(0xFF_FFFF)
+        })
+    }
+
+    fn on_cutoff(&mut self, position: &Position, column: usize) {
+        let move_bit = position.possible() & Position::column_mask(column);
+        let remaining = (Position::BOARD_SIZE - position.get_moves()) as u32;
+        let history = &mut self.history[position.get_moves() % 2][move_bit.trailing_zeros() as usize];
+        *history = history.saturating_add(remaining * remaining);
+    }
+
+    fn clone_box(&self) -> Box<dyn MoveOrdering> {
+        Box::new(self.clone())
+    }
+}
+
+/// Default constructor for the `HistoryOrdering` struct.
+impl Default for HistoryOrdering {
+    fn default() -> HistoryOrdering {
+        HistoryOrdering { history: [[0; 64]; 2] }
+    }
+}
+
+/// A move ordering using the killer heuristic, which breaks ties between moves creating the
+/// same number of winning threats in favour of the two most recent moves to cause a cut-off
+/// after the same number of moves.
+#[derive(Debug, Clone)]
+pub struct KillerOrdering {
+    /// The two most recent cut-off moves at each number of moves played, most recent first.
+    killers: [[Option<usize>; 2]; Position::BOARD_SIZE + 1],
+}
+
+impl KillerOrdering {
+    /// Creates a new killer ordering with no killer moves.
+    pub fn new() -> KillerOrdering {
+        Self::default()
+    }
+}
+
+impl MoveOrdering for KillerOrdering {
+    fn order_moves(
+        &self,
+        position: &Position,
+        possible_moves: u64,
+        columns: &[usize; Position::WIDTH],
+        _tt_move: Option<usize>,
+    ) -> MoveSorter {
+        let killers = &self.killers[position.get_moves()];
+        sort_moves(possible_moves, columns, |column, move_bit| {
+            let bonus = match killers {
+                [Some(first), _] if *first == column => 2,
+                [_, Some(second)] if *second == column => 1,
+                _ => 0,
+            };
+            (position.score_move(move_bit) as u32) << 2 | bonus
+        })
+    }
+
+    fn on_cutoff(&mut self, position: &Position, column: usize) {
+        let killers = &mut self.killers[position.get_moves()];
+        if killers[0] != Some(column) {
+            killers[1] = killers[0];
+            killers[0] = Some(column);
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn MoveOrdering> {
+        Box::new(self.clone())
+    }
+}
+
+/// Default constructor for the `KillerOrdering` struct.
+impl Default for KillerOrdering {
+    fn default() -> KillerOrdering {
+        KillerOrdering { killers: [[None; 2]; Position::BOARD_SIZE + 1] }
+    }
+}
+
+/// A move ordering which explores the best move stored in the transposition table first,
+/// followed by the remaining moves in the order given by another ordering.
+#[derive(Debug, Clone, Default)]
+pub struct TTMoveFirst<O: MoveOrdering + Clone + 'static>(pub O);
+
+impl<O: MoveOrdering + Clone + 'static> MoveOrdering for TTMoveFirst<O> {
+    fn order_moves(
+        &self,
+        position: &Position,
+        possible_moves: u64,
+        columns: &[usize; Position::WIDTH],
+        tt_move: Option<usize>,
+    ) -> MoveSorter {
+        let mut moves = self.0.order_moves(position, possible_moves, columns, tt_move);
+        if let Some(column) = tt_move {
+            moves.move_to_front(column);
+        }
+        moves
+    }
+
+    fn on_cutoff(&mut self, position: &Position, column: usize) {
+        self.0.on_cutoff(position, column);
+    }
+
+    fn clone_box(&self) -> Box<dyn MoveOrdering> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
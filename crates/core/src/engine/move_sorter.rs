@@ -9,10 +9,17 @@ pub struct MoveEntry {
     pub score: u8,
 }
 
+/// A move stored in a `MoveSorter`, with a score wide enough for the internal move orderings.
+#[derive(Debug, Copy, Clone, Default)]
+struct SortEntry {
+    column: usize,
+    score: u32,
+}
+
 /// A fixed-size container that stores a list of moves sorted by score.
 pub struct MoveSorter {
     size: usize,
-    entries: [MoveEntry; Position::WIDTH],
+    entries: [SortEntry; Position::WIDTH],
 }
 
 impl MoveSorter {
@@ -24,6 +31,13 @@ impl MoveSorter {
     /// Adds a move to the sorter and inserts it at the correct position.
     #[inline(always)]
     pub fn add(&mut self, column: usize, score: u8) {
+        self.add_weighted(column, score as u32);
+    }
+
+    /// Adds a move with a wider score than `add` allows, used by the move orderings which
+    /// combine several heuristics into a single score.
+    #[inline(always)]
+    pub(crate) fn add_weighted(&mut self, column: usize, score: u32) {
         let mut pos = self.size;
         while pos > 0 && self.entries[pos - 1].score > score {
             self.entries[pos] = self.entries[pos - 1];
@@ -33,6 +47,15 @@ impl MoveSorter {
         self.entries[pos].score = score;
         self.size += 1;
     }
+
+    /// Moves the given column to the front of the sorter, if it has been added, so that it is
+    /// returned first.
+    #[inline(always)]
+    pub fn move_to_front(&mut self, column: usize) {
+        if let Some(pos) = self.entries[..self.size].iter().position(|entry| entry.column == column) {
+            self.entries[pos..self.size].rotate_left(1);
+        }
+    }
 }
 
 /// Implements the `Iterator` trait to allow looping over moves from best to worst.
@@ -55,7 +78,7 @@ impl Default for MoveSorter {
     fn default() -> MoveSorter {
         MoveSorter {
             size: 0,
-            entries: [MoveEntry::default(); Position::WIDTH],
+            entries: [SortEntry::default(); Position::WIDTH],
         }
     }
 }
\ No newline at end of file
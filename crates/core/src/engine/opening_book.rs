@@ -28,6 +28,12 @@ impl OpeningBook {
     ///
     /// This is the key function that allows an opening book to be embedded within the executable.
     pub fn from_static_bytes(bytes: &'static [u8]) -> Result<OpeningBook, bincode::Error> {
+        Self::from_bytes(bytes)
+    }
+
+    /// Creates an `OpeningBook` by deserialising from a byte slice, such as the contents of
+    /// a book file which has been loaded into memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<OpeningBook, bincode::Error> {
         bincode::deserialize(bytes)
     }
 
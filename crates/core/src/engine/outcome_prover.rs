@@ -0,0 +1,38 @@
+//! A common interface for searches which prove the outcome of Connect Four positions.
+
+use crate::Position;
+
+/// A search which can prove whether a position is won or lost for the current player.
+///
+/// Win/loss queries are cheaper to answer than finding a position's exact score, as the
+/// search only needs to prove a single bound. This trait is implemented by the negamax
+/// `Solver`, which tests the bound with a null-window search, and by the
+/// `ProofNumberSolver`, which is often faster at proving wins in sharp tactical positions.
+///
+/// All methods assume that the given position is valid and not won by either player.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{OutcomeProver, Position, ProofNumberSolver, Solver};
+///
+///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+///  let mut provers: [Box<dyn OutcomeProver>; 2] = [
+///      Box::new(Solver::empty()),
+///      Box::new(ProofNumberSolver::new()),
+///  ];
+///
+///  // Every prover agrees that the current player wins
+///  for prover in provers.iter_mut() {
+///      assert!(prover.is_win(&pos));
+///      assert!(!prover.is_loss(&pos));
+///  }
+/// ```
+pub trait OutcomeProver {
+    /// Indicates whether the current player can force a win from the given position.
+    fn is_win(&mut self, position: &Position) -> bool;
+
+    /// Indicates whether the opponent can force a win from the given position, so that the
+    /// current player cannot avoid losing.
+    fn is_loss(&mut self, position: &Position) -> bool;
+}
\ No newline at end of file
@@ -0,0 +1,375 @@
+//! Provides a depth-first proof-number search for proving wins and losses.
+
+use crate::{OutcomeProver, Position, SearchLimits, Solver};
+use std::collections::HashMap;
+use web_time::Instant;
+
+/// The proof and disproof numbers of a node, relative to the player to move.
+///
+/// `phi` is the minimum number of leaf nodes which must be proven to show that the player to
+/// move achieves their goal, and `delta` is the number which must be proven to show that they
+/// don't. Storing the numbers relative to the player to move means that both players' nodes
+/// are handled in the same way.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ProofNumbers {
+    phi: u32,
+    delta: u32,
+}
+
+impl ProofNumbers {
+    /// The numbers of a node in which the player to move achieves their goal.
+    const WIN: ProofNumbers = ProofNumbers { phi: 0, delta: INFINITY };
+
+    /// The numbers of a node in which the player to move fails to achieve their goal.
+    const LOSS: ProofNumbers = ProofNumbers { phi: INFINITY, delta: 0 };
+}
+
+/// An entry in the solver's table, holding a position's proof numbers along with the number of
+/// nodes which have been explored to find them.
+#[derive(Debug, Copy, Clone)]
+struct TableEntry {
+    numbers: ProofNumbers,
+    work: usize,
+}
+
+/// The proof number of a node which can never be proven.
+const INFINITY: u32 = u32::MAX;
+
+/// The outcome which the attacking player is trying to prove.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Goal {
+    /// The attacker must win the game.
+    Win,
+    /// The attacker must win or draw the game.
+    NonLoss,
+}
+
+/// A solver which proves whether Connect Four positions are won or lost, using depth-first
+/// proof-number search (df-pn).
+///
+/// Rather than finding a position's exact score, proof-number search only answers whether the
+/// current player can force a given outcome. It expands the most-proving node of the game
+/// tree at each step, which is the node that contributes most to proving or disproving the
+/// outcome. In sharp tactical positions, where one side has few safe replies, this often
+/// proves a win much faster than an alpha-beta search.
+///
+/// The search is run depth-first with thresholds on the proof numbers, and stores the proof
+/// numbers of expanded positions in a hash table which is cleared before each query. The
+/// table holds at most `max_table_size` entries. When it is full, the half of its entries
+/// which took the fewest nodes to find are evicted, so that the results of large subtrees are
+/// kept. Evicted positions are searched again if they are needed, so a smaller table never
+/// changes the answers to queries, but can make them much slower if it is far smaller than
+/// the number of nodes they explore.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Position, ProofNumberSolver};
+///
+///  let mut solver = ProofNumberSolver::new();
+///
+///  // The current player wins this position, so can't lose it
+///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+///  assert_eq!(solver.prove_win(&pos), true);
+///  assert_eq!(solver.prove_loss(&pos), false);
+///
+///  // The current player loses this position
+///  let pos = Position::from_moves("76461241141").unwrap();
+///  assert_eq!(solver.prove_win(&pos), false);
+///  assert_eq!(solver.prove_loss(&pos), true);
+///
+///  // Queries give the same answers with a table much smaller than the search
+///  let pos = Position::from_moves("274552224131661").unwrap();
+///  solver.max_table_size = 20000;
+///  assert_eq!(solver.prove_win(&pos), false);
+///  assert!(solver.explored_positions > 4 * solver.max_table_size);
+/// ```
+#[derive(Debug)]
+pub struct ProofNumberSolver {
+    /// A counter for the number of nodes expanded in the last query.
+    pub explored_positions: usize,
+
+    /// Limits on the resources used by each query, which are applied by `try_prove_win` and
+    /// `try_prove_loss`.
+    pub limits: SearchLimits,
+
+    /// The maximum number of entries held in the solver's table, which defaults to
+    /// `DEFAULT_TABLE_SIZE`.
+    pub max_table_size: usize,
+
+    /// The proof numbers of the positions expanded in the current query.
+    table: HashMap<u64, TableEntry>,
+
+    /// The outcome which the attacking player is trying to prove in the current query.
+    goal: Goal,
+
+    /// The parity of the number of moves in positions where the attacker is to move.
+    attacker_parity: usize,
+
+    /// The time at which the current query must be abandoned, if it has a time limit.
+    deadline: Option<Instant>,
+
+    /// The number of explored nodes at which the query's limits should next be checked.
+    next_limit_check: usize,
+
+    /// Whether the current query has been abandoned for exceeding its limits.
+    aborted: bool,
+}
+
+impl ProofNumberSolver {
+    /// The number of nodes explored between checks of the search's time limit.
+    const TIME_CHECK_INTERVAL: usize = 1 << 12;
+
+    /// The default maximum number of entries held in the solver's table, which use around
+    /// 100 MB of memory.
+    pub const DEFAULT_TABLE_SIZE: usize = 1 << 22;
+
+    /// Creates a new `ProofNumberSolver` instance with no search limits.
+    pub fn new() -> ProofNumberSolver {
+        Self::default()
+    }
+
+    /// Resets the solver's state, releasing the memory used by its table.
+    pub fn reset(&mut self) {
+        self.explored_positions = 0;
+        self.table = HashMap::new();
+    }
+
+    /// Proves whether the current player can force a win from the given position.
+    ///
+    /// Assumes that the given position is valid and not won by either player. The solver's
+    /// search limits are not applied by this function, so it always finds the answer.
+    pub fn prove_win(&mut self, position: &Position) -> bool {
+        self.prove(position, Goal::Win, SearchLimits::NONE)
+            .expect("A search without limits should always complete.")
+    }
+
+    /// Proves whether the opponent can force a win from the given position.
+    ///
+    /// Assumes that the given position is valid and not won by either player. The solver's
+    /// search limits are not applied by this function, so it always finds the answer.
+    pub fn prove_loss(&mut self, position: &Position) -> bool {
+        self.prove(position, Goal::NonLoss, SearchLimits::NONE)
+            .map(|non_loss| !non_loss)
+            .expect("A search without limits should always complete.")
+    }
+
+    /// Proves whether the current player can force a win from the given position, within
+    /// the solver's search limits.
+    ///
+    /// # Returns
+    /// Whether the position is won, or `None` if the search was abandoned.
+    pub fn try_prove_win(&mut self, position: &Position) -> Option<bool> {
+        self.prove(position, Goal::Win, self.limits)
+    }
+
+    /// Proves whether the opponent can force a win from the given position, within the
+    /// solver's search limits.
+    ///
+    /// # Returns
+    /// Whether the position is lost, or `None` if the search was abandoned.
+    pub fn try_prove_loss(&mut self, position: &Position) -> Option<bool> {
+        self.prove(position, Goal::NonLoss, self.limits).map(|non_loss| !non_loss)
+    }
+
+    /// Proves whether the current player can achieve the given goal from a position,
+    /// abandoning the search if any of the given limits are exceeded.
+    fn prove(&mut self, position: &Position, goal: Goal, limits: SearchLimits) -> Option<bool> {
+        self.explored_positions = 0;
+        self.table.clear();
+        self.goal = goal;
+        self.attacker_parity = position.get_moves() % 2;
+        self.deadline = limits.max_time.map(|max_time| Instant::now() + max_time);
+        self.aborted = false;
+
+        let node_limit = limits.max_nodes.map_or(usize::MAX, |max_nodes| max_nodes.saturating_add(1));
+        self.next_limit_check = match self.deadline {
+            Some(_) => node_limit.min(Self::TIME_CHECK_INTERVAL),
+            None => node_limit,
+        };
+
+        let numbers = match self.evaluate(position) {
+            Some(numbers) => numbers,
+            None => self.search(position, ProofNumbers { phi: INFINITY, delta: INFINITY }, limits),
+        };
+
+        (!self.aborted).then_some(numbers.phi == 0)
+    }
+
+    /// Returns the proof numbers of a terminal position, relative to the player to move, or
+    /// `None` if the position must be searched.
+    fn evaluate(&self, position: &Position) -> Option<ProofNumbers> {
+        // A full board is a draw, which is only good enough for the attacker if they
+        // are trying to avoid losing
+        if position.get_moves() == Position::BOARD_SIZE {
+            let attacker_to_move = position.get_moves() % 2 == self.attacker_parity;
+            let attacker_proven = self.goal == Goal::NonLoss;
+            return Some(if attacker_to_move == attacker_proven { ProofNumbers::WIN } else { ProofNumbers::LOSS });
+        }
+
+        // The player to move wins the game, which achieves their goal whichever side they are
+        if position.can_win_next() {
+            return Some(ProofNumbers::WIN);
+        }
+
+        // The opponent is guaranteed to win next move
+        if position.possible_non_losing_moves() == 0 {
+            return Some(ProofNumbers::LOSS);
+        }
+
+        None
+    }
+
+    /// Returns the proof numbers of a child position which is about to be searched.
+    ///
+    /// Positions which haven't been expanded yet are estimated from their number of moves.
+    fn child_numbers(&self, position: &Position) -> ProofNumbers {
+        if let Some(entry) = self.table.get(&position.get_key()) {
+            return entry.numbers;
+        }
+        self.evaluate(position).unwrap_or_else(|| ProofNumbers {
+            phi: 1,
+            delta: position.possible_non_losing_moves().count_ones(),
+        })
+    }
+
+    /// Expands a non-terminal position until its proof numbers reach one of the given
+    /// thresholds, returning the position's new proof numbers.
+    fn search(&mut self, position: &Position, thresholds: ProofNumbers, limits: SearchLimits) -> ProofNumbers {
+        let start_positions = self.explored_positions;
+        self.explored_positions += 1;
+        if self.explored_positions >= self.next_limit_check {
+            self.check_limits(limits);
+        }
+
+        // Generates the children of the position, which are all non-losing moves
+        let possible_moves = position.possible_non_losing_moves();
+        let mut children = [(Position::new(), ProofNumbers::LOSS); Position::WIDTH];
+        let mut child_count = 0;
+        for &column in Solver::COLUMNS.iter() {
+            if possible_moves & Position::column_mask(column) > 0 {
+                let mut child = *position;
+                child.play(column);
+                children[child_count] = (child, self.child_numbers(&child));
+                child_count += 1;
+            }
+        }
+        let children = &mut children[..child_count];
+
+        loop {
+            let numbers = Self::combine(children);
+            if numbers.phi >= thresholds.phi || numbers.delta >= thresholds.delta || self.aborted {
+                if !self.aborted {
+                    self.store(position.get_key(), numbers, self.explored_positions - start_positions);
+                }
+                return numbers;
+            }
+
+            // Finds the most-proving child, which has the smallest disproof number,
+            // along with the second-smallest disproof number
+            let mut best = 0;
+            let mut second_delta = INFINITY;
+            for (i, (_, child_numbers)) in children.iter().enumerate().skip(1) {
+                if child_numbers.delta < children[best].1.delta {
+                    second_delta = children[best].1.delta;
+                    best = i;
+                } else if child_numbers.delta < second_delta {
+                    second_delta = child_numbers.delta;
+                }
+            }
+
+            // Searches the child until it's no longer the most-proving child, or until
+            // the position's thresholds are reached
+            let (child, child_numbers) = children[best];
+            let child_thresholds = ProofNumbers {
+                phi: thresholds.delta - (numbers.delta - child_numbers.phi),
+                delta: thresholds.phi.min(second_delta.saturating_add(1)),
+            };
+            children[best].1 = self.search_child(&child, child_thresholds, limits);
+        }
+    }
+
+    /// Stores the proof numbers of a position in the table, along with the number of nodes
+    /// explored to find them, first evicting entries if the table is full.
+    fn store(&mut self, key: u64, numbers: ProofNumbers, work: usize) {
+        if let Some(entry) = self.table.get_mut(&key) {
+            *entry = TableEntry { numbers, work: entry.work.saturating_add(work) };
+            return;
+        }
+
+        if self.table.len() >= self.max_table_size {
+            // Evicts the half of the entries with the least work, which are cheapest to find again
+            let mut works: Vec<usize> = self.table.values().map(|entry| entry.work).collect();
+            let median = works.len() / 2;
+            let threshold = *works.select_nth_unstable(median).1;
+            self.table.retain(|_, entry| entry.work > threshold);
+        }
+        self.table.insert(key, TableEntry { numbers, work });
+    }
+
+    /// Searches a child position, returning immediately if its stored proof numbers have
+    /// already reached the given thresholds.
+    fn search_child(&mut self, position: &Position, thresholds: ProofNumbers, limits: SearchLimits) -> ProofNumbers {
+        let numbers = self.child_numbers(position);
+        if numbers.phi >= thresholds.phi || numbers.delta >= thresholds.delta {
+            return numbers;
+        }
+        self.search(position, thresholds, limits)
+    }
+
+    /// Combines the proof numbers of a position's children into its own proof numbers.
+    ///
+    /// The player to move only needs one child in which their opponent fails, but needs every
+    /// child to fail to be disproven.
+    fn combine(children: &[(Position, ProofNumbers)]) -> ProofNumbers {
+        let phi = children.iter().map(|(_, numbers)| numbers.delta).min().unwrap_or(INFINITY);
+        let delta = if children.iter().any(|(_, numbers)| numbers.phi == INFINITY) {
+            INFINITY
+        } else {
+            let sum: u64 = children.iter().map(|(_, numbers)| numbers.phi as u64).sum();
+            sum.min(INFINITY as u64 - 1) as u32
+        };
+        ProofNumbers { phi, delta }
+    }
+
+    /// Checks whether the search has exceeded any of the given limits.
+    #[cold]
+    fn check_limits(&mut self, limits: SearchLimits) {
+        let node_limit_exceeded = limits.max_nodes.is_some_and(|max_nodes| self.explored_positions > max_nodes);
+        let time_limit_exceeded = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        self.aborted |= node_limit_exceeded || time_limit_exceeded;
+
+        let node_limit = limits.max_nodes.map_or(usize::MAX, |max_nodes| max_nodes.saturating_add(1));
+        self.next_limit_check = match self.deadline {
+            Some(_) => node_limit.min(self.explored_positions + Self::TIME_CHECK_INTERVAL),
+            None => node_limit,
+        };
+    }
+}
+
+impl OutcomeProver for ProofNumberSolver {
+    fn is_win(&mut self, position: &Position) -> bool {
+        self.prove_win(position)
+    }
+
+    fn is_loss(&mut self, position: &Position) -> bool {
+        self.prove_loss(position)
+    }
+}
+
+/// Default constructor for the `ProofNumberSolver` struct.
+impl Default for ProofNumberSolver {
+    fn default() -> ProofNumberSolver {
+        ProofNumberSolver {
+            explored_positions: 0,
+            limits: SearchLimits::NONE,
+            max_table_size: Self::DEFAULT_TABLE_SIZE,
+            table: HashMap::new(),
+            goal: Goal::Win,
+            attacker_parity: 0,
+            deadline: None,
+            next_limit_check: usize::MAX,
+            aborted: false,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,359 @@
+//! Provides a generator for training puzzles taken from random reachable positions.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use rand::rngs::StdRng;
+use rand::{rng, Rng, SeedableRng};
+use crate::{Difficulty, Position, Solver};
+
+/// The kind of a puzzle, which decides what the solver of the puzzle must find.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PuzzleKind {
+    /// The current player has exactly one winning move, and must find it.
+    Win,
+    /// The current player has exactly one move which doesn't lose, and must find it.
+    Draw,
+}
+
+impl Display for PuzzleKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PuzzleKind::Win => "Win",
+            PuzzleKind::Draw => "Draw",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A puzzle, given by a position with a single correct move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    /// The move sequence reaching the puzzle's position, as a string of 1-based columns.
+    pub moves: String,
+
+    /// The kind of the puzzle.
+    pub kind: PuzzleKind,
+
+    /// The 0-based column of the only correct move.
+    pub solution: usize,
+
+    /// The exact score of the correct move.
+    pub score: i8,
+
+    /// The number of moves the puzzle looks ahead. For a win puzzle, this is the number of
+    /// moves the current player needs to win, counting the correct move. For a draw puzzle,
+    /// this is the number of moves the opponent needs to win after the most resilient
+    /// incorrect move.
+    pub depth: usize,
+
+    /// The difficulty of the puzzle, from its depth.
+    pub difficulty: Difficulty,
+
+    /// The solution line of perfect play, which starts with the correct move, as a string of
+    /// 1-based columns. It can be appended to `moves` and parsed with `Position::from_moves`.
+    pub solution_line: String,
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} {:?} {}", self.moves, self.kind, self.depth, self.difficulty, self.solution_line)
+    }
+}
+
+/// The criteria which generated puzzles must meet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PuzzleFilter {
+    /// The kind of puzzle to generate, or `None` to generate either kind.
+    pub kind: Option<PuzzleKind>,
+
+    /// The difficulty of puzzle to generate, or `None` to generate any difficulty.
+    pub difficulty: Option<Difficulty>,
+
+    /// The smallest puzzle depth to generate.
+    pub min_depth: usize,
+
+    /// The largest puzzle depth to generate.
+    pub max_depth: usize,
+
+    /// The smallest number of moves played to reach a puzzle's position.
+    pub min_moves: usize,
+
+    /// The largest number of moves played to reach a puzzle's position.
+    pub max_moves: usize,
+}
+
+impl PuzzleFilter {
+    /// Creates a new filter accepting puzzles of either kind and any difficulty, which look
+    /// at least 2 moves ahead and are reached after 12 to 30 moves.
+    pub fn new() -> PuzzleFilter {
+        Self::default()
+    }
+
+    /// Returns a copy of the filter which only accepts puzzles of the given kind.
+    pub fn with_kind(mut self, kind: PuzzleKind) -> PuzzleFilter {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Returns a copy of the filter which only accepts puzzles of the given difficulty.
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> PuzzleFilter {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Returns a copy of the filter which only accepts puzzles with depths in the given range.
+    pub fn with_depth(mut self, min_depth: usize, max_depth: usize) -> PuzzleFilter {
+        self.min_depth = min_depth;
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns a copy of the filter which only accepts puzzles reached after a number of
+    /// moves in the given range.
+    pub fn with_moves(mut self, min_moves: usize, max_moves: usize) -> PuzzleFilter {
+        self.min_moves = min_moves;
+        self.max_moves = max_moves;
+        self
+    }
+
+    /// Indicates whether a puzzle of the given kind and depth, reached after the given number
+    /// of moves, is accepted by the filter.
+    pub fn accepts(&self, kind: PuzzleKind, depth: usize, moves: usize) -> bool {
+        self.kind.is_none_or(|k| k == kind)
+            && self.difficulty.is_none_or(|d| d == PuzzleGenerator::depth_difficulty(depth))
+            && (self.min_depth..=self.max_depth).contains(&depth)
+            && (self.min_moves..=self.max_moves).contains(&moves)
+    }
+}
+
+/// Default constructor for the `PuzzleFilter` struct.
+impl Default for PuzzleFilter {
+    fn default() -> PuzzleFilter {
+        PuzzleFilter {
+            kind: None,
+            difficulty: None,
+            min_depth: 2,
+            max_depth: Position::BOARD_SIZE,
+            min_moves: 12,
+            max_moves: 30,
+        }
+    }
+}
+
+/// A generator for "win in N" and "only drawing move" puzzles.
+///
+/// Puzzles are found by playing random moves from the start of the game, up to a number of
+/// moves chosen from the filter's range, without ever completing a 4-alignment. Positions
+/// which are lost for the current player are discarded, and every possible move in the
+/// remaining positions is scored with `Solver::get_all_move_scores_with_hint`, hinted by
+/// the position's score. The position becomes a puzzle if:
+/// - Exactly one move wins, which makes a win puzzle
+/// - No move wins and exactly one move draws, which makes a draw puzzle
+///
+/// Puzzles which don't meet the generator's `filter` are discarded, as are positions which
+/// were already generated, including their mirror images. The random moves are drawn from
+/// a seedable generator, so the same seed and filter always generate the same puzzles.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Position, PuzzleFilter, PuzzleGenerator, PuzzleKind};
+///
+///  let mut generator = PuzzleGenerator::with_seed(7);
+///  generator.filter = PuzzleFilter::new().with_kind(PuzzleKind::Win).with_depth(2, 3).with_moves(20, 30);
+///
+///  let puzzles = generator.generate(3);
+///  assert_eq!(puzzles.len(), 3);
+///  for puzzle in puzzles.iter() {
+///      assert!(puzzle.kind == PuzzleKind::Win && (2..=3).contains(&puzzle.depth));
+///
+///      // The solution line ends with a winning move, made on the puzzle's final move
+///      let line = format!("{}{}", puzzle.moves, puzzle.solution_line);
+///      let last = line.chars().last().unwrap().to_digit(10).unwrap() as usize - 1;
+///      assert!(Position::from_moves(&line[..line.len() - 1]).unwrap().is_winning_move(last));
+///      assert_eq!(puzzle.solution_line.len(), 2 * puzzle.depth - 1);
+///  }
+///
+///  // The same seed generates the same puzzles
+///  let mut other = PuzzleGenerator::with_seed(7);
+///  other.filter = generator.filter;
+///  assert_eq!(other.generate(3), puzzles);
+/// ```
+#[derive(Debug)]
+pub struct PuzzleGenerator {
+    /// The solver used to score the moves of each candidate position.
+    pub solver: Solver,
+
+    /// The criteria which generated puzzles must meet.
+    pub filter: PuzzleFilter,
+
+    /// The number of random positions tried for each puzzle before `next_puzzle` gives up.
+    pub max_attempts: usize,
+
+    /// The random number generator used to play random moves.
+    rng: StdRng,
+}
+
+impl PuzzleGenerator {
+    /// Creates a new puzzle generator with a random seed and the default filter.
+    pub fn new() -> PuzzleGenerator {
+        Self::with_seed(rng().random())
+    }
+
+    /// Creates a new puzzle generator with the given seed and the default filter.
+    pub fn with_seed(seed: u64) -> PuzzleGenerator {
+        PuzzleGenerator {
+            solver: Solver::new(),
+            filter: PuzzleFilter::new(),
+            max_attempts: 10_000,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the difficulty of a puzzle with the given depth.
+    pub fn depth_difficulty(depth: usize) -> Difficulty {
+        match depth {
+            0..=2 => Difficulty::Easy,
+            3..=4 => Difficulty::Medium,
+            5..=7 => Difficulty::Hard,
+            _ => Difficulty::Impossible,
+        }
+    }
+
+    /// Generates up to the given number of distinct puzzles, stopping early if `next_puzzle`
+    /// fails to find one.
+    pub fn generate(&mut self, count: usize) -> Vec<Puzzle> {
+        let mut puzzles = Vec::with_capacity(count);
+        let mut seen = HashSet::new();
+        let mut attempts = 0;
+
+        while puzzles.len() < count && attempts < self.max_attempts {
+            let Some(puzzle) = self.next_puzzle() else { break };
+            let key = Position::from_moves(&puzzle.moves)
+                .expect("A puzzle's moves should be valid.")
+                .get_key();
+
+            if seen.insert(key) {
+                puzzles.push(puzzle);
+            } else {
+                attempts += 1;
+            }
+        }
+
+        puzzles
+    }
+
+    /// Generates the next puzzle meeting the filter, or `None` if no puzzle was found within
+    /// `max_attempts` random positions.
+    pub fn next_puzzle(&mut self) -> Option<Puzzle> {
+        for _ in 0..self.max_attempts {
+            let Some(moves) = self.random_moves() else { continue };
+            if let Some(puzzle) = self.find_puzzle(&moves) {
+                return Some(puzzle);
+            }
+        }
+        None
+    }
+
+    /// Checks whether the position reached by the given move sequence is a puzzle meeting
+    /// the filter, returning the puzzle if so.
+    ///
+    /// Returns `None` if the sequence is invalid.
+    pub fn find_puzzle(&mut self, moves: &str) -> Option<Puzzle> {
+        let position = Position::from_moves(moves).ok()?;
+        if !(self.filter.min_moves..=self.filter.max_moves).contains(&position.get_moves()) {
+            return None;
+        }
+
+        // Solving the position first rules out most positions before every move is scored,
+        // as lost positions are never puzzles, and the score decides the puzzle's kind
+        let position_score = self.solver.solve(&position);
+        let kind = match position_score {
+            score if score > 0 => PuzzleKind::Win,
+            0 => PuzzleKind::Draw,
+            _ => return None,
+        };
+        if self.filter.kind.is_some_and(|k| k != kind) {
+            return None;
+        }
+
+        let scores = self.solver.get_all_move_scores_with_hint(&position, position_score);
+        let (kind, solution, score, depth) = Self::classify(&position, &scores)?;
+        if !self.filter.accepts(kind, depth, position.get_moves()) {
+            return None;
+        }
+
+        let analysis = self.solver.analyse(&position, 1);
+        Some(Puzzle {
+            moves: moves.to_string(),
+            kind,
+            solution,
+            score,
+            depth,
+            difficulty: Self::depth_difficulty(depth),
+            solution_line: analysis[0].principal_variation_string(),
+        })
+    }
+
+    /// Classifies a position from the scores of its moves, returning the puzzle's kind,
+    /// solution, score and depth, or `None` if the position isn't a puzzle.
+    fn classify(position: &Position, scores: &[Option<i8>; Position::WIDTH]) -> Option<(PuzzleKind, usize, i8, usize)> {
+        let moves = position.get_moves();
+        let columns_with = |accept: fn(i8) -> bool| -> Vec<usize> {
+            (0..Position::WIDTH).filter(|&column| scores[column].is_some_and(accept)).collect()
+        };
+        let winning_moves = columns_with(|score| score > 0);
+        let drawing_moves = columns_with(|score| score == 0);
+
+        if let [solution] = winning_moves[..] {
+            let score = scores[solution]?;
+            return Some((PuzzleKind::Win, solution, score, Self::moves_to_win(moves, score)));
+        }
+        if let ([], [solution]) = (&winning_moves[..], &drawing_moves[..]) {
+            // The slowest loss is the hardest incorrect move to refute
+            let slowest_loss = scores.iter().flatten().copied().filter(|&score| score < 0).max()?;
+            return Some((PuzzleKind::Draw, *solution, 0, Self::moves_to_win(moves + 1, -slowest_loss)));
+        }
+        None
+    }
+
+    /// Returns the number of moves the current player needs to win a position with the
+    /// given number of moves played and a positive score.
+    fn moves_to_win(moves: usize, score: i8) -> usize {
+        (Position::BOARD_SIZE + 1 - moves) / 2 + 1 - score as usize
+    }
+
+    /// Plays random moves from the start of the game up to a number of moves chosen from the
+    /// filter's range, never completing a 4-alignment, and returns the move sequence.
+    ///
+    /// Returns `None` if the game reaches a position where every move completes one.
+    fn random_moves(&mut self) -> Option<String> {
+        let max_moves = self.filter.max_moves.min(Position::BOARD_SIZE - 1);
+        let min_moves = self.filter.min_moves.min(max_moves);
+        let target = self.rng.random_range(min_moves..=max_moves);
+
+        let mut position = Position::new();
+        let mut moves = String::with_capacity(target);
+        while position.get_moves() < target {
+            let columns: Vec<usize> = (0..Position::WIDTH)
+                .filter(|&column| position.is_playable(column) && !position.is_winning_move(column))
+                .collect();
+            if columns.is_empty() {
+                return None;
+            }
+
+            let column = columns[self.rng.random_range(0..columns.len())];
+            position.play(column);
+            moves.push_str(&(column + 1).to_string());
+        }
+
+        Some(moves)
+    }
+}
+
+/// Default constructor for the `PuzzleGenerator` struct.
+impl Default for PuzzleGenerator {
+    fn default() -> PuzzleGenerator {
+        PuzzleGenerator::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,517 @@
+//! Provides a rule-based evaluator in the style of Victor Allis's VICTOR program, which explains
+//! why the second player can't lose a position using strategic rules rather than search.
+
+use std::fmt::{Display, Formatter};
+use web_time::Instant;
+use serde::{Deserialize, Serialize};
+use crate::{Position, SearchLimits};
+
+/// The number of possible 4-alignments on the board.
+const GROUP_COUNT: usize = 69;
+
+/// Masks of every possible 4-alignment on the board, known as groups.
+const GROUPS: [u64; GROUP_COUNT] = const {
+    let mut groups = [0; GROUP_COUNT];
+    let mut count = 0;
+
+    // The direction of each alignment as a column and row step
+    let directions: [(usize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+    let mut d = 0;
+    while d < directions.len() {
+        let (column_step, row_step) = directions[d];
+        let mut column = 0;
+        while column + 3 * column_step < Position::WIDTH {
+            let mut row = 0;
+            while row < Position::HEIGHT {
+                let end_row = row as isize + 3 * row_step;
+                if end_row >= 0 && end_row < Position::HEIGHT as isize {
+                    let mut group = 0;
+                    let mut i = 0;
+                    while i < 4 {
+                        let square_row = (row as isize + i as isize * row_step) as usize;
+                        group |= square_bit(column + i * column_step, square_row);
+                        i += 1;
+                    }
+                    groups[count] = group;
+                    count += 1;
+                }
+                row += 1;
+            }
+            column += 1;
+        }
+        d += 1;
+    }
+    groups
+};
+
+/// Returns the bitboard mask of a single square.
+const fn square_bit(column: usize, row: usize) -> u64 {
+    1 << (row + column * (Position::HEIGHT + 1))
+}
+
+/// Indicates whether a square's row is odd when counting rows from 1 at the bottom of the board,
+/// which is the convention used by the rules' definitions.
+fn is_odd_row(square: u64) -> bool {
+    Square::from_bit(square).row.is_multiple_of(2)
+}
+
+/// Returns a mask of the columns containing any of the given squares.
+fn columns_of(squares: u64) -> u8 {
+    (0..Position::WIDTH)
+        .filter(|&column| squares & Position::column_mask(column) > 0)
+        .fold(0, |columns, column| columns | 1 << column)
+}
+
+/// Returns a list of the individual square masks in a mask of squares.
+fn square_bits(mut squares: u64) -> Vec<u64> {
+    let mut bits = Vec::with_capacity(squares.count_ones() as usize);
+    while squares > 0 {
+        bits.push(squares & squares.wrapping_neg());
+        squares &= squares - 1;
+    }
+    bits
+}
+
+/// A square of the board.
+///
+/// Squares are displayed in the notation used by Allis, with a letter for the column from `a`
+/// to `g` followed by the row from 1 to 6, counting from the bottom of the board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Square {
+    /// The 0-based index of the square's column.
+    pub column: usize,
+    /// The 0-based index of the square's row, counting from the bottom of the board.
+    pub row: usize,
+}
+
+impl Square {
+    /// Returns the square of a single-bit bitboard mask.
+    fn from_bit(bit: u64) -> Square {
+        let index = bit.trailing_zeros() as usize;
+        Square { column: index / (Position::HEIGHT + 1), row: index % (Position::HEIGHT + 1) }
+    }
+
+    /// Returns the squares of a bitboard mask, ordered by column and then by row.
+    fn from_mask(mask: u64) -> Vec<Square> {
+        square_bits(mask).into_iter().map(Square::from_bit).collect()
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", (b'a' + self.column as u8) as char, self.row + 1)
+    }
+}
+
+/// The strategic rules which the second player can follow to refute the first player's groups.
+///
+/// Rows are counted from 1 at the bottom of the board. By answering each move directly above
+/// it (playing 'follow-up'), the second player can claim every square on an even row, so the
+/// rules describe how this can be relied on or locally deviated from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Rule {
+    /// Two empty squares directly above each other, where the upper square is on an even row.
+    /// The second player gets the upper square by playing follow-up, refuting every group which
+    /// contains it.
+    Claimeven,
+
+    /// Two directly playable squares in different columns. When the first player plays one of
+    /// them, the second player plays the other, refuting every group which contains both.
+    Baseinverse,
+
+    /// Two empty squares directly above each other, where the upper square is on an odd row.
+    /// The second player gets one of the two squares, refuting every group which contains both.
+    Vertical,
+
+    /// A group of the second player which can be completed using only Claimevens. The group is
+    /// completed before any group of the first player which has a square above its empty
+    /// squares in each of their columns, refuting those groups, along with every group
+    /// refuted by its Claimevens.
+    Aftereven,
+
+    /// Two Verticals in different columns. The second player also gets one of the two upper
+    /// squares, refuting every group which contains both of them.
+    Lowinverse,
+
+    /// Three empty squares directly above each other in each of two columns, where the upper
+    /// squares are on an even row. The second player gets one of the two upper squares, one of
+    /// the two middle squares and one of the upper two squares in each column, and if the lower
+    /// square of a column is directly playable, one of that square and the upper square of the
+    /// other column.
+    Highinverse,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Rule::Claimeven => "Claimeven",
+            Rule::Baseinverse => "Baseinverse",
+            Rule::Vertical => "Vertical",
+            Rule::Aftereven => "Aftereven",
+            Rule::Lowinverse => "Lowinverse",
+            Rule::Highinverse => "Highinverse",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A single application of a rule to a position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleApplication {
+    /// The rule which is applied.
+    pub rule: Rule,
+
+    /// The empty squares used by the rule.
+    pub squares: Vec<Square>,
+
+    /// The second player's group completed by an Aftereven, or `None` for other rules.
+    pub group: Option<Vec<Square>>,
+
+    /// The first player's groups which the rule refutes.
+    pub refuted_groups: Vec<Vec<Square>>,
+}
+
+/// A set of compatible rules which together refute every group the first player could complete,
+/// proving that the second player can't lose.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// The column played by the second player before the rules apply, if it was their move.
+    pub first_move: Option<usize>,
+
+    /// The applications of each rule.
+    pub rules: Vec<RuleApplication>,
+
+    /// Whether the rules also prove a win for the second player, which is the case when they
+    /// include an Aftereven, as its group is completed before the first player can win.
+    pub second_player_wins: bool,
+}
+
+/// An application of a rule which has been found in a position, stored as bitboard masks.
+#[derive(Debug, Clone)]
+struct Solution {
+    rule: Rule,
+    /// The empty squares used by the rule.
+    squares: u64,
+    /// The squares of the Claimevens used by the rule, which may be shared with other rules.
+    claimevens: u64,
+    /// A mask of the columns of the Claimevens used by the rule.
+    claimeven_columns: u8,
+    /// A mask of the columns where the rule answers moves in a different column, which changes
+    /// which player gets the squares above.
+    inverse_columns: u8,
+    /// The second player's group completed by an Aftereven.
+    group: Option<u64>,
+    /// A mask of the indices of the problems refuted by the rule.
+    refuted: u128,
+}
+
+impl Solution {
+    /// Indicates whether two rules can be followed together.
+    ///
+    /// Rules must use different squares, except for Claimevens shared by both rules, which are
+    /// played in the same way by each. Rules which answer moves in a different column rely on
+    /// the order in which their columns are filled, so they must use different columns from
+    /// each other and from any Claimevens.
+    fn is_compatible(&self, other: &Solution) -> bool {
+        let shared = self.squares & other.squares;
+        shared & !(self.claimevens & other.claimevens) == 0
+            && self.inverse_columns & (other.inverse_columns | other.claimeven_columns) == 0
+            && other.inverse_columns & self.claimeven_columns == 0
+    }
+}
+
+/// A rule-based evaluator in the style of VICTOR, Victor Allis's knowledge-based Connect Four
+/// program.
+///
+/// Rather than searching the game tree, the evaluator finds the strategic rules which apply to
+/// a position, and searches for a compatible set of them which refutes every group the first
+/// player could still complete. Such a set proves that the second player can't lose, and
+/// explains why in terms a person can follow: Claimeven, Baseinverse, Vertical, Aftereven,
+/// Lowinverse and Highinverse (see `Rule`).
+///
+/// The rules apply when the first player is to move. When the second player is to move, each
+/// of their moves is tried before applying the rules. The evaluator is incomplete, so failing
+/// to find a rule set doesn't mean that the first player wins. The `explain_rules` binary checks
+/// the rule sets found for a test file against the positions' known scores.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Position, Rule, RuleEvaluator};
+///
+///  let mut evaluator = RuleEvaluator::new();
+///
+///  // The first player's groups are all refuted, and the second player completes a group
+///  // using Claimevens, so the second player wins
+///  let pos = Position::from_moves("34651743747475571565").unwrap();
+///  let rule_set = evaluator.find_rules(&pos).unwrap();
+///  assert!(rule_set.rules.iter().any(|application| application.rule == Rule::Aftereven));
+///  assert!(rule_set.second_player_wins);
+///
+///  // The first player wins from the start of the game, so no rule set can be found
+///  assert!(evaluator.find_rules(&Position::new()).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RuleEvaluator {
+    /// Limits on the resources used by each search for a rule set, where `max_nodes` limits the
+    /// number of partial rule sets considered.
+    pub limits: SearchLimits,
+
+    /// A counter for the number of partial rule sets considered in the last search.
+    pub explored_positions: usize,
+}
+
+impl RuleEvaluator {
+    /// The default maximum number of partial rule sets considered in a search.
+    pub const DEFAULT_MAX_NODES: usize = 100_000;
+
+    /// Creates a new evaluator with the default node limit.
+    pub fn new() -> RuleEvaluator {
+        Self::default()
+    }
+
+    /// Searches for a set of rules proving that the second player can't lose the given
+    /// position, returning `None` if no such set is found within the evaluator's limits.
+    ///
+    /// Assumes that the given position is valid and not won by either player.
+    pub fn find_rules(&mut self, position: &Position) -> Option<RuleSet> {
+        self.explored_positions = 0;
+        let deadline = self.limits.max_time.map(|max_time| Instant::now() + max_time);
+
+        if position.get_moves().is_multiple_of(2) {
+            return self.find_rules_for_first_player(position, deadline);
+        }
+
+        // The second player is to move, so each of their moves is tried first
+        for column in (0..Position::WIDTH).filter(|&column| position.is_playable(column)) {
+            if position.is_winning_move(column) {
+                return Some(RuleSet { first_move: Some(column), rules: Vec::new(), second_player_wins: true });
+            }
+
+            let mut child = *position;
+            child.play(column);
+            if let Some(rule_set) = self.find_rules_for_first_player(&child, deadline) {
+                return Some(RuleSet { first_move: Some(column), ..rule_set });
+            }
+        }
+        None
+    }
+
+    /// Searches for a rule set in a position where the first player is to move.
+    fn find_rules_for_first_player(&mut self, position: &Position, deadline: Option<Instant>) -> Option<RuleSet> {
+        // The problems are the first player's groups which the second player hasn't blocked
+        let second = position.position ^ position.mask;
+        let problems: Vec<u64> = GROUPS.iter().copied().filter(|&group| group & second == 0).collect();
+        let solutions = Self::find_solutions(position, &problems);
+
+        let mut chosen = Vec::new();
+        let uncovered = if problems.is_empty() { 0 } else { u128::MAX >> (128 - problems.len()) };
+        let mut search = RuleSearch { solutions: &solutions, limits: self.limits, deadline, nodes: &mut self.explored_positions };
+        if !search.search(uncovered, &mut chosen) {
+            return None;
+        }
+
+        let rules: Vec<RuleApplication> = chosen.iter().map(|&index| {
+            let solution = &solutions[index];
+            RuleApplication {
+                rule: solution.rule,
+                squares: Square::from_mask(solution.squares),
+                group: solution.group.map(Square::from_mask),
+                refuted_groups: (0..problems.len())
+                    .filter(|&problem| solution.refuted & (1 << problem) > 0)
+                    .map(|problem| Square::from_mask(problems[problem]))
+                    .collect(),
+            }
+        }).collect();
+
+        // Any Aftereven group is completed by the second player, as the first player can't win
+        let second_player_wins = rules.iter().any(|application| application.rule == Rule::Aftereven);
+        Some(RuleSet { first_move: None, rules, second_player_wins })
+    }
+
+    /// Finds every application of the rules in a position where the first player is to move,
+    /// which refutes at least one of the given problems.
+    fn find_solutions(position: &Position, problems: &[u64]) -> Vec<Solution> {
+        let empty = (0..Position::WIDTH).fold(0, |mask, column| mask | Position::column_mask(column)) & !position.mask;
+        let playable = position.possible();
+
+        // Returns a mask of the problems which satisfy a condition
+        let refuted_by = |condition: &dyn Fn(u64) -> bool| -> u128 {
+            problems.iter()
+                .enumerate()
+                .filter(|&(_, &problem)| condition(problem))
+                .fold(0, |mask, (index, _)| mask | 1 << index)
+        };
+        let contains_all = |squares: u64| refuted_by(&|problem| problem & squares == squares);
+
+        let mut solutions = Vec::new();
+        let mut add = |rule, squares, claimevens, inverse: bool, group, refuted: u128| {
+            if refuted > 0 {
+                let claimeven_columns = columns_of(claimevens);
+                let inverse_columns = if inverse { columns_of(squares) } else { 0 };
+                solutions.push(Solution { rule, squares, claimevens, claimeven_columns, inverse_columns, group, refuted });
+            }
+        };
+
+        // The empty squares which are directly above another empty square
+        let upper_squares = square_bits(empty & (empty << 1));
+
+        // Claimevens and Verticals
+        for &upper in upper_squares.iter() {
+            let squares = upper | upper >> 1;
+            if is_odd_row(upper) {
+                add(Rule::Vertical, squares, 0, false, None, contains_all(squares));
+            } else {
+                add(Rule::Claimeven, squares, squares, false, None, refuted_by(&|problem| problem & upper > 0));
+            }
+        }
+
+        // Baseinverses
+        let playable_squares = square_bits(playable);
+        for (i, &a) in playable_squares.iter().enumerate() {
+            for &b in playable_squares[i + 1..].iter() {
+                add(Rule::Baseinverse, a | b, 0, true, None, contains_all(a | b));
+            }
+        }
+
+        // Afterevens, for the second player's groups whose empty squares are all even Claimevens
+        for &group in GROUPS.iter() {
+            let group_empty = group & empty;
+            if group & position.position > 0 || group_empty == 0 {
+                continue;
+            }
+            let uppers = square_bits(group_empty);
+            if uppers.iter().any(|&square| is_odd_row(square) || empty & square >> 1 == 0) {
+                continue;
+            }
+
+            // The squares above the group's highest empty square in each of its columns
+            let columns_above: Vec<u64> = (0..Position::WIDTH)
+                .map(|column| group_empty & Position::column_mask(column))
+                .filter(|&column_empty| column_empty > 0)
+                .map(|column_empty| {
+                    let highest = 1 << (63 - column_empty.leading_zeros());
+                    Position::column_mask(Square::from_bit(highest).column) & !((highest << 1) - 1)
+                })
+                .collect();
+
+            let claimevens = group_empty | group_empty >> 1;
+            let refuted = refuted_by(&|problem| {
+                problem & group_empty > 0 || columns_above.iter().all(|&above| problem & above > 0)
+            });
+            add(Rule::Aftereven, claimevens, claimevens, false, Some(group), refuted);
+        }
+
+        // Lowinverses, from pairs of Verticals in different columns
+        let verticals: Vec<u64> = upper_squares.iter().copied().filter(|&upper| is_odd_row(upper)).collect();
+        for (i, &a) in verticals.iter().enumerate() {
+            for &b in verticals[i + 1..].iter() {
+                if Square::from_bit(a).column == Square::from_bit(b).column {
+                    continue;
+                }
+                let squares = a | a >> 1 | b | b >> 1;
+                let refuted = contains_all(a | b) | contains_all(a | a >> 1) | contains_all(b | b >> 1);
+                add(Rule::Lowinverse, squares, 0, true, None, refuted);
+            }
+        }
+
+        // Highinverses, from pairs of three empty squares whose upper square is on an even row
+        let triples: Vec<u64> = upper_squares.iter()
+            .copied()
+            .filter(|&upper| !is_odd_row(upper) && Square::from_bit(upper).row >= 2 && empty & upper >> 2 > 0)
+            .collect();
+        for (i, &a) in triples.iter().enumerate() {
+            for &b in triples[i + 1..].iter() {
+                if Square::from_bit(a).column == Square::from_bit(b).column {
+                    continue;
+                }
+                let squares = a | a >> 1 | a >> 2 | b | b >> 1 | b >> 2;
+                let mut refuted = contains_all(a | b)
+                    | contains_all(a >> 1 | b >> 1)
+                    | contains_all(a | a >> 1)
+                    | contains_all(b | b >> 1);
+                if playable & a >> 2 > 0 {
+                    refuted |= contains_all(a >> 2 | b);
+                }
+                if playable & b >> 2 > 0 {
+                    refuted |= contains_all(b >> 2 | a);
+                }
+                add(Rule::Highinverse, squares, 0, true, None, refuted);
+            }
+        }
+
+        solutions
+    }
+}
+
+/// The state of a search for a set of compatible solutions which refutes every problem.
+struct RuleSearch<'a> {
+    solutions: &'a [Solution],
+    limits: SearchLimits,
+    deadline: Option<Instant>,
+    nodes: &'a mut usize,
+}
+
+impl RuleSearch<'_> {
+    /// Searches for a set of solutions which are compatible with the chosen solutions, and
+    /// refute each of the uncovered problems, adding them to the chosen solutions if found.
+    fn search(&mut self, uncovered: u128, chosen: &mut Vec<usize>) -> bool {
+        if uncovered == 0 {
+            return true;
+        }
+
+        *self.nodes += 1;
+        let node_limit = self.limits.max_nodes.unwrap_or(RuleEvaluator::DEFAULT_MAX_NODES);
+        if *self.nodes > node_limit || self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return false;
+        }
+
+        // The solutions which are compatible with all chosen solutions
+        let candidates: Vec<usize> = (0..self.solutions.len())
+            .filter(|&index| chosen.iter().all(|&other| self.solutions[index].is_compatible(&self.solutions[other])))
+            .collect();
+
+        // Branches on the uncovered problem with the fewest solutions
+        let mut best_problem = None;
+        let mut best_count = usize::MAX;
+        let mut problems = uncovered;
+        while problems > 0 {
+            let problem = problems & problems.wrapping_neg();
+            problems &= problems - 1;
+            let count = candidates.iter().filter(|&&index| self.solutions[index].refuted & problem > 0).count();
+            if count < best_count {
+                best_problem = Some(problem);
+                best_count = count;
+            }
+            if count == 0 {
+                return false;
+            }
+        }
+        let problem = best_problem.expect("There should be at least one uncovered problem.");
+
+        // Tries the solutions refuting the most uncovered problems first
+        let mut options: Vec<usize> = candidates.into_iter()
+            .filter(|&index| self.solutions[index].refuted & problem > 0)
+            .collect();
+        options.sort_by_key(|&index| std::cmp::Reverse((self.solutions[index].refuted & uncovered).count_ones()));
+
+        for index in options {
+            chosen.push(index);
+            if self.search(uncovered & !self.solutions[index].refuted, chosen) {
+                return true;
+            }
+            chosen.pop();
+        }
+        false
+    }
+}
+
+/// Default constructor for the `RuleEvaluator` struct.
+impl Default for RuleEvaluator {
+    fn default() -> RuleEvaluator {
+        RuleEvaluator {
+            limits: SearchLimits::new().with_max_nodes(Self::DEFAULT_MAX_NODES),
+            explored_positions: 0,
+        }
+    }
+}
\ No newline at end of file
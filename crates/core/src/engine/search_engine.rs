@@ -0,0 +1,104 @@
+//! A common interface for engines which evaluate Connect Four positions.
+
+use crate::{Position, Solver};
+use std::fmt::{Debug, Formatter};
+
+/// An engine which evaluates Connect Four positions and scores their possible moves.
+///
+/// Scores use the same scale as the `Solver`'s exact scores: positive if the current player
+/// will win, zero for a draw and negative if they will lose, with larger magnitudes for faster
+/// results. Engines which don't solve positions perfectly, such as heuristic searches, return
+/// their best estimate on this scale. This allows the `AIPlayer` and other tools to use any
+/// engine, including alternative searches, remote engines and test doubles.
+///
+/// All methods assume that the given position is valid and not won by either player.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{Engine, Position, Solver};
+///
+///  let mut engine: Box<dyn Engine> = Box::new(Solver::new());
+///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+///
+///  assert_eq!(engine.name(), "Solver");
+///  assert_eq!(engine.evaluate(&pos), 8);
+///  assert_eq!(engine.best_move(&pos), Some(4));
+/// ```
+pub trait Engine {
+    /// Returns the name of the engine, for display in tools and user interfaces.
+    fn name(&self) -> &str;
+
+    /// Evaluates a position, returning its score from the current player's perspective.
+    fn evaluate(&mut self, position: &Position) -> i8;
+
+    /// Calculates the scores for all possible next moves in the given position.
+    ///
+    /// Returns a fixed-size array where each index corresponds to a column, containing the
+    /// move's score if it is possible, or `None` if the column is full.
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH];
+
+    /// Calculates the scores for all possible next moves in a position whose score is expected
+    /// to be known, such as from a search on the previous turn.
+    ///
+    /// Engines which can't make use of the expected score ignore it, which is the default.
+    fn move_scores_with_hint(&mut self, position: &Position, _expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        self.move_scores(position)
+    }
+
+    /// Returns the highest-scoring move in the given position, or `None` if no moves are
+    /// possible.
+    ///
+    /// By default, all moves are scored with `move_scores`, and ties are broken in favour of
+    /// columns closer to the centre.
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        best_scored_move(&self.move_scores(position))
+    }
+
+    /// Resets the engine's state, such as any cached search results.
+    fn reset(&mut self);
+}
+
+/// Returns the column with the highest score, breaking ties in favour of columns closer to the
+/// centre, or `None` if no moves are possible.
+pub(crate) fn best_scored_move<T: Ord + Copy>(scores: &[Option<T>; Position::WIDTH]) -> Option<usize> {
+    Solver::COLUMNS
+        .iter()
+        .filter_map(|&column| scores[column].map(|score| (column, score)))
+        .reduce(|best, next| if next.1 > best.1 { next } else { best })
+        .map(|(column, _)| column)
+}
+
+/// Allows boxed engines, including trait objects, to be used wherever an engine is expected.
+impl<E: Engine + ?Sized> Engine for Box<E> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        (**self).evaluate(position)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        (**self).move_scores(position)
+    }
+
+    fn move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        (**self).move_scores_with_hint(position, expected_score)
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        (**self).best_move(position)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
+/// Allows AI players holding an engine trait object to be debug formatted.
+impl Debug for dyn Engine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Engine({})", self.name())
+    }
+}
\ No newline at end of file
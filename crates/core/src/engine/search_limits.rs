@@ -0,0 +1,42 @@
+//! Limits which can be placed on the amount of work done by a solver's search.
+
+use std::time::Duration;
+
+/// Limits on the resources used by a single search of the `Solver`.
+///
+/// Limits are only applied by the solver's `try_solve` method, which abandons its search and
+/// returns `None` once any limit is exceeded. By default, no limits are applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SearchLimits {
+    /// The maximum number of nodes which may be explored by each search thread.
+    pub max_nodes: Option<usize>,
+    /// The maximum amount of time which may be spent on a search.
+    pub max_time: Option<Duration>,
+}
+
+impl SearchLimits {
+    /// A set of limits which places no restrictions on a search.
+    pub const NONE: SearchLimits = SearchLimits { max_nodes: None, max_time: None };
+
+    /// Creates a new set of limits with no restrictions.
+    pub fn new() -> SearchLimits {
+        Self::default()
+    }
+
+    /// Returns a copy of the limits with a maximum number of nodes per search thread.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> SearchLimits {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Returns a copy of the limits with a maximum search time.
+    pub fn with_max_time(mut self, max_time: Duration) -> SearchLimits {
+        self.max_time = Some(max_time);
+        self
+    }
+
+    /// Indicates whether the limits place no restrictions on a search.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_nodes.is_none() && self.max_time.is_none()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,91 @@
+//! An observer interface for reporting the progress of a solver's search.
+
+use crate::Position;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A snapshot of the progress of a search, passed to a `SearchObserver`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SearchProgress {
+    /// The number of the current iteration of the binary search, starting from 1.
+    pub iteration: usize,
+    /// The lower bound of the current search window.
+    pub min: i8,
+    /// The upper bound of the current search window.
+    pub max: i8,
+    /// The score being tested by the current null-window search.
+    pub mid: i8,
+    /// The number of nodes explored so far in the search.
+    pub explored_positions: usize,
+    /// The time elapsed since the start of the search.
+    pub elapsed: Duration,
+}
+
+/// A trait for receiving progress reports from a `Solver`, such as for displaying the
+/// solver's 'thinking' progress in a GUI.
+///
+/// The solver calls the observer at the start of each search, at each iteration of the
+/// binary search over the position's score, and periodically as nodes are explored. All
+/// methods have empty default implementations, so observers only need to implement the
+/// events they're interested in. When no observer is attached to the solver, no progress
+/// is tracked.
+///
+/// Each progress report includes the time elapsed since the start of the search.
+pub trait SearchObserver: Send + Sync {
+    /// Called when the solver starts searching a position.
+    fn on_search_start(&mut self, _position: &Position) {}
+
+    /// Called at the start of each iteration of the binary search over the position's score.
+    fn on_iteration(&mut self, _progress: &SearchProgress) {}
+
+    /// Called each time the number of nodes given by `node_interval` have been explored.
+    fn on_nodes(&mut self, _progress: &SearchProgress) {}
+
+    /// Returns the number of nodes to explore between calls to `on_nodes`.
+    fn node_interval(&self) -> usize {
+        1 << 20
+    }
+}
+
+/// Allows solvers holding an observer to be debug formatted.
+impl Debug for dyn SearchObserver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SearchObserver")
+    }
+}
+
+/// An observer which forwards its reports to another observer shared between several
+/// solvers, such as the helpers used to score moves in parallel.
+pub(crate) struct SharedObserver {
+    /// The observer receiving the reports.
+    observer: Arc<Mutex<Box<dyn SearchObserver>>>,
+    /// The node interval of the shared observer, read once so that it needn't be locked.
+    node_interval: usize,
+}
+
+impl SharedObserver {
+    /// Creates a new observer forwarding its reports to the given shared observer.
+    pub(crate) fn new(observer: Arc<Mutex<Box<dyn SearchObserver>>>) -> SharedObserver {
+        let node_interval = observer.lock().unwrap().node_interval();
+        SharedObserver { observer, node_interval }
+    }
+}
+
+impl SearchObserver for SharedObserver {
+    fn on_search_start(&mut self, position: &Position) {
+        self.observer.lock().unwrap().on_search_start(position);
+    }
+
+    fn on_iteration(&mut self, progress: &SearchProgress) {
+        self.observer.lock().unwrap().on_iteration(progress);
+    }
+
+    fn on_nodes(&mut self, progress: &SearchProgress) {
+        self.observer.lock().unwrap().on_nodes(progress);
+    }
+
+    fn node_interval(&self) -> usize {
+        self.node_interval
+    }
+}
\ No newline at end of file
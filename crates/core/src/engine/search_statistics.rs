@@ -0,0 +1,91 @@
+//! Detailed statistics about a solver's search, used for diagnostics and tuning.
+
+use crate::Position;
+use std::time::Duration;
+
+/// A collection of statistics gathered by the `Solver` during a single search.
+///
+/// Collecting statistics is disabled by default, and can be enabled by setting the solver's
+/// `statistics` field to `Some(SearchStatistics::new())`. When enabled, the statistics are
+/// cleared at the start of each `solve` call, in the same way as the explored positions counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchStatistics {
+    /// The number of transposition table look-ups performed.
+    pub tt_probes: usize,
+    /// The number of transposition table look-ups which found a valid entry.
+    pub tt_hits: usize,
+    /// The number of beta cut-offs caused by each move, indexed by the move's position in
+    /// the sorted move order.
+    pub cutoffs: [usize; Position::WIDTH],
+    /// The number of nodes which were cut off using the transposition table entries of their
+    /// children, without searching any moves.
+    pub enhanced_cutoffs: usize,
+    /// The number of nodes explored at each search depth, indexed by the number of empty cells.
+    pub nodes_per_depth: [usize; Position::BOARD_SIZE + 1],
+    /// The number of positions found in the opening book.
+    pub book_hits: usize,
+    /// The number of positions which were not found in the opening book.
+    pub book_misses: usize,
+    /// The time spent on each iteration of the binary search over the position's score.
+    pub iteration_times: Vec<Duration>,
+}
+
+impl SearchStatistics {
+    /// Creates a new, empty set of statistics.
+    pub fn new() -> SearchStatistics {
+        Self::default()
+    }
+
+    /// Returns the total number of nodes explored across all depths.
+    pub fn total_nodes(&self) -> usize {
+        self.nodes_per_depth.iter().sum()
+    }
+
+    /// Returns the total number of beta cut-offs.
+    pub fn total_cutoffs(&self) -> usize {
+        self.cutoffs.iter().sum()
+    }
+
+    /// Returns the proportion of transposition table look-ups which found a valid entry.
+    pub fn tt_hit_rate(&self) -> f64 {
+        if self.tt_probes == 0 {
+            0.0
+        } else {
+            self.tt_hits as f64 / self.tt_probes as f64
+        }
+    }
+
+    /// Adds the counters from another set of statistics to this one.
+    ///
+    /// Iteration times are appended, so that the times from several searches can be combined.
+    pub fn merge(&mut self, other: &SearchStatistics) {
+        self.tt_probes += other.tt_probes;
+        self.tt_hits += other.tt_hits;
+        for (total, &count) in self.cutoffs.iter_mut().zip(other.cutoffs.iter()) {
+            *total += count;
+        }
+        for (total, &count) in self.nodes_per_depth.iter_mut().zip(other.nodes_per_depth.iter()) {
+            *total += count;
+        }
+        self.enhanced_cutoffs += other.enhanced_cutoffs;
+        self.book_hits += other.book_hits;
+        self.book_misses += other.book_misses;
+        self.iteration_times.extend_from_slice(&other.iteration_times);
+    }
+}
+
+/// Default constructor for the `SearchStatistics` struct.
+impl Default for SearchStatistics {
+    fn default() -> SearchStatistics {
+        SearchStatistics {
+            tt_probes: 0,
+            tt_hits: 0,
+            cutoffs: [0; Position::WIDTH],
+            enhanced_cutoffs: 0,
+            nodes_per_depth: [0; Position::BOARD_SIZE + 1],
+            book_hits: 0,
+            book_misses: 0,
+            iteration_times: Vec::new(),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+//! A transposition table which can be shared between several solvers.
+
+use crate::{TTEntry, TTFlag, TTStore, TranspositionTable};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A transposition table which can be shared between several solvers, such as a group of
+/// `AIPlayer` instances, so that each benefits from the results found by the others.
+///
+/// The table is a cheap handle to its entries: cloning it creates another handle to the same
+/// entries. Any number of solvers can read and write entries concurrently without locks.
+///
+/// Each entry is stored in two atomic words: the entry's data, and the data XORed with the
+/// position's full key. An entry is only accepted if XORing the two words gives back the key
+/// being looked up, so entries torn by concurrent writes from different threads are rejected
+/// rather than misread. As the full key is verified, the table's size has no lower bound.
+/// Entries are always overwritten when a new result is stored.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{BookSource, Position, SharedTranspositionTable, Solver};
+///
+///  let table = SharedTranspositionTable::with_memory(16 << 20);
+///  let mut solvers: Vec<Solver> = (0..2)
+///      .map(|_| Solver::builder()
+///          .opening_book(BookSource::None)
+///          .shared_table(table.clone())
+///          .build()
+///          .unwrap())
+///      .collect();
+///
+///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+///  assert_eq!(solvers[0].solve(&pos), 8);
+///
+///  // The second solver reuses the results found by the first
+///  assert_eq!(solvers[1].solve(&pos), 8);
+///  assert!(solvers[1].explored_positions < solvers[0].explored_positions);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedTranspositionTable {
+    /// The entries shared by every handle to the table, each stored as the data XORed with
+    /// the key followed by the data.
+    entries: Arc<Vec<[AtomicU64; 2]>>,
+}
+
+impl SharedTranspositionTable {
+    /// The generation stored in every entry. This is never zero, so that unused entries are
+    /// never valid.
+    const AGE: u16 = 1;
+
+    /// Creates a new empty shared table with the same number of entries as the default
+    /// `TranspositionTable`.
+    pub fn new() -> SharedTranspositionTable {
+        Self::default()
+    }
+
+    /// Creates a new empty shared table with at least the given number of entries.
+    ///
+    /// The size is rounded up to the nearest prime number to help avoid collisions.
+    pub fn with_size(entries: usize) -> SharedTranspositionTable {
+        Self::allocate(TranspositionTable::next_prime(entries.max(2)))
+    }
+
+    /// Creates a new empty shared table which fits within the given memory budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: The maximum amount of memory to allocate for the table's entries, in bytes.
+    pub fn with_memory(bytes: usize) -> SharedTranspositionTable {
+        let entries = bytes / size_of::<[AtomicU64; 2]>();
+        Self::allocate(TranspositionTable::previous_prime(entries.max(2)))
+    }
+
+    /// Allocates a table with exactly the given number of entries.
+    fn allocate(size: usize) -> SharedTranspositionTable {
+        SharedTranspositionTable {
+            entries: Arc::new((0..size).map(|_| [AtomicU64::new(0), AtomicU64::new(0)]).collect()),
+        }
+    }
+
+    /// Returns the number of entries in the table.
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the amount of memory used by the table's entries, in bytes.
+    pub fn memory(&self) -> usize {
+        self.size() * size_of::<[AtomicU64; 2]>()
+    }
+
+    /// Returns whether two handles refer to the same table.
+    pub fn ptr_eq(&self, other: &SharedTranspositionTable) -> bool {
+        Arc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    /// Clears every entry of the table, for every solver sharing it.
+    ///
+    /// Resetting a solver leaves its shared table intact, as entries stay correct for their
+    /// positions across searches with the same rules. The table must be cleared when the rules
+    /// change, such as when a solver sharing it changes its move filter, which
+    /// `Solver::set_move_filter` does automatically.
+    ///
+    /// This must only be called while no solver sharing the table is searching, as entries
+    /// stored during the reset may survive it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{SharedTranspositionTable, TTFlag, TTStore};
+    ///
+    ///  let table = SharedTranspositionTable::with_size(1000);
+    ///  let handle = table.clone();
+    ///  table.put(42, 5, TTFlag::Exact, 10, None);
+    ///  assert!(handle.get(42).is_some());
+    ///
+    ///  // Clearing the table through one handle clears it for every handle
+    ///  handle.reset();
+    ///  assert!(table.get(42).is_none());
+    ///  assert!(table.get(0).is_none());
+    /// ```
+    pub fn reset(&self) {
+        for entry in self.entries.iter() {
+            entry[0].store(0, Ordering::Relaxed);
+            entry[1].store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Calculates the table index for a given position's key.
+    #[inline(always)]
+    fn index(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+}
+
+impl TTStore for SharedTranspositionTable {
+    /// Stores a new entry in the table, overwriting any existing entry at the calculated index.
+    fn put(&self, key: u64, value: i8, flag: TTFlag, depth: u8, best_move: Option<usize>) {
+        let data = TTEntry { key: key as u32, value, flag, depth, best_move, age: Self::AGE }.pack();
+        let entry = &self.entries[self.index(key)];
+        entry[0].store(key ^ data, Ordering::Relaxed);
+        entry[1].store(data, Ordering::Relaxed);
+    }
+
+    /// Retrieves an entry from the table if it exists and is valid.
+    fn get(&self, key: u64) -> Option<TTEntry> {
+        let entry = &self.entries[self.index(key)];
+        let check = entry[0].load(Ordering::Relaxed);
+        let data = entry[1].load(Ordering::Relaxed);
+
+        // Checks that the words weren't torn by a concurrent write, and that the entry is used
+        let entry = TTEntry::unpack(data);
+        if check ^ data == key && entry.age == Self::AGE {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+}
+
+/// Default constructor for the `SharedTranspositionTable` struct.
+impl Default for SharedTranspositionTable {
+    fn default() -> SharedTranspositionTable {
+        Self::with_size(TranspositionTable::MAX_SIZE)
+    }
+}
\ No newline at end of file
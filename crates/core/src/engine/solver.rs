@@ -1,11 +1,38 @@
 //! Provides the core solving logic for the Connect Four AI.
 
-use crate::{MoveSorter, OpeningBook, Position, TTFlag, TranspositionTable};
+use crate::{
+    Engine,
+    MoveAnalysis,
+    MoveFilter,
+    MoveOrdering,
+    Outcome,
+    OutcomeProver,
+    SolverBuilder,
+    OpeningBook,
+    Position,
+    SearchObserver,
+    SearchLimits,
+    SearchProgress,
+    SearchStatistics,
+    SharedTranspositionTable,
+    ThreatOrdering,
+    TTFlag,
+    TTStore,
+    TranspositionTable
+};
+use super::move_filter::allowed_moves;
+use super::search_observer::SharedObserver;
+use rayon::prelude::*;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use web_time::Instant;
 
 // This line embeds a book file directly into the program's binary
 // The path is relative to the current source file (solver.rs)
-const OPENING_BOOK_BYTES: &[u8] = include_bytes!("books/default-book.bin");
+pub(crate) const OPENING_BOOK_BYTES: &[u8] = include_bytes!("books/default-book.bin");
 
 /// A strong solver for finding the exact score of Connect Four positions.
 ///
@@ -15,16 +42,65 @@ const OPENING_BOOK_BYTES: &[u8] = include_bytes!("books/default-book.bin");
 /// - Score-based move ordering to prioritise stronger moves
 /// - A transposition table to cache results of previously seen positions
 /// - A binary search on the score for faster convergence
+/// - An optional multithreaded search, using the Lazy SMP algorithm
 #[derive(Debug)]
 pub struct Solver {
     /// A counter for the number of nodes explored in the last `solve` call.
     pub explored_positions: usize,
 
+    /// The number of threads used to search each position.
+    ///
+    /// With more than one thread, `solve` runs a Lazy SMP search: every thread searches the
+    /// same position with a different move ordering, sharing results through the lock-free
+    /// transposition table, and the first thread to finish provides the score.
+    pub threads: usize,
+
     /// The transposition table used for caching search results.
     pub transposition_table: TranspositionTable,
 
+    /// An optional table shared with other solvers, which is used instead of the solver's own
+    /// `transposition_table` when set.
+    pub shared_table: Option<SharedTranspositionTable>,
+
+    /// The strategy used to order the moves explored at each node of the search.
+    pub move_ordering: Box<dyn MoveOrdering>,
+
+    /// Whether the search uses enhanced transposition cut-offs.
+    ///
+    /// Before searching a node's children, the search checks their transposition table
+    /// entries for bounds on the node's score. These can cause a cut-off or narrow the search
+    /// window without searching any children.
+    pub enhanced_cutoffs: bool,
+
     /// The opening book for instant lookups of early-game positions.
     pub opening_book: Option<OpeningBook>,
+
+    /// Detailed statistics for the last `solve` call, which are only collected if enabled.
+    pub statistics: Option<SearchStatistics>,
+
+    /// An optional observer which receives progress reports during each search.
+    pub observer: Option<Box<dyn SearchObserver>>,
+
+    /// Limits on the resources used by each search, which are applied by `try_solve`.
+    pub limits: SearchLimits,
+
+    /// Whether `reset` releases the transposition table's memory back to the allocator.
+    ///
+    /// The table is always allocated lazily by the first search which needs it, so releasing
+    /// it stops idle solvers from holding on to its memory between games.
+    pub release_table_on_reset: bool,
+
+    /// Whether the solver's `Engine` implementation evaluates each possible move concurrently,
+    /// using `get_all_move_scores_parallel`.
+    pub parallel_move_scores: bool,
+
+    /// An optional restriction on the moves which may be played during each search, which is
+    /// changed with `set_move_filter` so that the transposition table is cleared.
+    pub(crate) move_filter: Option<Arc<dyn MoveFilter>>,
+
+    /// The solvers used by `get_all_move_scores_parallel` to solve each move concurrently,
+    /// which are created from this solver's configuration when they are first needed.
+    pub(crate) root_solvers: Vec<Solver>,
 }
 
 
@@ -45,12 +121,44 @@ impl Solver {
         Self::default()
     }
 
+    /// Creates a new `SolverBuilder` for configuring a `Solver` instance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver};
+    ///
+    ///  // A solver with a smaller transposition table and no opening book
+    ///  let mut solver = Solver::builder()
+    ///      .transposition_table_size(1 << 20)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///
+    ///  let pos = Position::from_moves("76461241141").unwrap();
+    ///  assert_eq!(solver.solve(&pos), -1)
+    /// ```
+    pub fn builder() -> SolverBuilder {
+        SolverBuilder::new()
+    }
+
     /// Creates a new `Solver` instance which is empty (without an opening book).
     pub fn empty() -> Solver {
         Solver {
             explored_positions: 0,
+            threads: 1,
             transposition_table: TranspositionTable::new(),
-            opening_book: None
+            shared_table: None,
+            move_ordering: Box::new(ThreatOrdering),
+            enhanced_cutoffs: true,
+            opening_book: None,
+            statistics: None,
+            observer: None,
+            limits: SearchLimits::NONE,
+            release_table_on_reset: false,
+            parallel_move_scores: false,
+            move_filter: None,
+            root_solvers: Vec::new(),
         }
     }
 
@@ -58,14 +166,191 @@ impl Solver {
     ///
     /// Returns whether the opening book was successfully loaded.
     pub fn load_opening_book(&mut self, path: &Path) -> bool {
+        self.root_solvers.clear();
         self.opening_book = OpeningBook::load(path).ok();
         self.opening_book.is_some()
     }
 
+    /// Attempts to load a transposition table saved with `TranspositionTable::save` from the
+    /// given path, replacing the solver's table to warm-start its searches.
+    ///
+    /// Returns whether the table was successfully loaded.
+    pub fn load_transposition_table(&mut self, path: &Path) -> bool {
+        match TranspositionTable::load(path) {
+            Ok(table) => {
+                self.transposition_table = table;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Enables the collection of detailed search statistics for subsequent searches.
+    pub fn enable_statistics(&mut self) {
+        self.statistics = Some(SearchStatistics::new());
+    }
+
+    /// Attaches an observer to the solver, which receives progress reports during each search.
+    ///
+    /// In a multithreaded search, only the progress of the main search thread is reported.
+    pub fn set_observer(&mut self, observer: impl SearchObserver + 'static) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Restricts the moves which may be played in each position of the solver's searches.
+    ///
+    /// The restriction is respected by every search, including `get_all_move_scores`, which
+    /// gives no score for forbidden moves. As the scores of restricted positions differ from
+    /// their true scores, the opening book is not used while a filter is set, and the
+    /// transposition table is cleared whenever the filter changes. If the filter is not
+    /// symmetric, positions are stored in the table separately from their mirror images.
+    ///
+    /// A shared transposition table is also cleared whenever the filter changes, for every
+    /// solver sharing it, so this must not be called while any of those solvers is searching.
+    /// Solvers sharing a table must all use the same filter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{ForbiddenColumns, Position, Solver};
+    ///
+    ///  let mut solver = Solver::new();
+    ///  let pos = Position::from_moves("4455").unwrap();
+    ///  assert_eq!(solver.get_all_move_scores(&pos)[2], Some(18));
+    ///
+    ///  // Without the 3rd and 6th columns, the first player can't complete their open three
+    ///  solver.set_move_filter(ForbiddenColumns::new(&[2, 5]).for_player(1));
+    ///  let scores = solver.get_all_move_scores(&pos);
+    ///  assert_eq!((scores[2], scores[5]), (None, None));
+    ///  assert!(scores.iter().flatten().all(|&score| score < 18));
+    /// ```
+    ///
+    /// Restricted and unrestricted searches never see each other's results in a shared table:
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, ForbiddenColumns, Position, SharedTranspositionTable, Solver};
+    ///
+    ///  let new_solver = |table: Option<SharedTranspositionTable>| {
+    ///      let builder = Solver::builder().opening_book(BookSource::None);
+    ///      match table {
+    ///          Some(table) => builder.shared_table(table).build().unwrap(),
+    ///          None => builder.transposition_table_memory(4).build().unwrap(),
+    ///      }
+    ///  };
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///  let filter = ForbiddenColumns::new(&[4]).for_player(2);
+    ///
+    ///  // Finds the expected scores with separate solvers
+    ///  let mut restricted = new_solver(None);
+    ///  restricted.set_move_filter(filter);
+    ///  let expected_restricted = restricted.solve(&pos);
+    ///  let expected = new_solver(None).solve(&pos);
+    ///  assert_eq!(expected, 8);
+    ///  assert_ne!(expected_restricted, expected);
+    ///
+    ///  // Solves with a filter, then without it, and with it again, on the same shared table
+    ///  let mut solver = new_solver(Some(SharedTranspositionTable::with_memory(16 << 20)));
+    ///  solver.set_move_filter(filter);
+    ///  assert_eq!(solver.solve(&pos), expected_restricted);
+    ///  solver.clear_move_filter();
+    ///  assert_eq!(solver.solve(&pos), expected);
+    ///  solver.set_move_filter(filter);
+    ///  assert_eq!(solver.solve(&pos), expected_restricted);
+    /// ```
+    pub fn set_move_filter(&mut self, filter: impl MoveFilter + 'static) {
+        self.replace_move_filter(Some(Arc::new(filter)));
+    }
+
+    /// Removes any restriction on the moves which may be played, clearing the transposition
+    /// table, and any shared table, if a filter was set.
+    pub fn clear_move_filter(&mut self) {
+        self.replace_move_filter(None);
+    }
+
+    /// Returns the filter restricting the moves which may be played, if one is set.
+    pub fn move_filter(&self) -> Option<&dyn MoveFilter> {
+        self.move_filter.as_deref()
+    }
+
+    /// Replaces the solver's move filter, clearing the transposition tables if it changes.
+    pub(crate) fn replace_move_filter(&mut self, filter: Option<Arc<dyn MoveFilter>>) {
+        let unchanged = match (&self.move_filter, &filter) {
+            (Some(current), Some(new)) => Arc::ptr_eq(current, new),
+            (current, new) => current.is_none() && new.is_none(),
+        };
+        if !unchanged {
+            self.move_filter = filter;
+            self.transposition_table.reset();
+            if let Some(shared_table) = &self.shared_table {
+                shared_table.reset();
+            }
+        }
+    }
+
+    /// Allocates the solver's own transposition table before a search, unless a shared table
+    /// is used, and records whether the search's moves are restricted.
+    fn prepare_table(&mut self) {
+        if self.shared_table.is_none() {
+            self.transposition_table.allocate();
+            if self.move_filter.is_some() {
+                self.transposition_table.mark_restricted();
+            }
+        }
+    }
+
+    /// Returns the solver's opening book, unless its moves are restricted, as the book's
+    /// scores assume that every move may be played.
+    fn book(&self) -> Option<&OpeningBook> {
+        self.opening_book.as_ref().filter(|_| self.move_filter.is_none())
+    }
+
+    /// Returns a mask of the moves which may be played in a position under the solver's
+    /// move filter.
+    fn allowed_moves(&self, position: &Position) -> u64 {
+        match &self.move_filter {
+            Some(filter) => allowed_moves(filter.as_ref(), position),
+            None => position.possible(),
+        }
+    }
+
+    /// Indicates whether the current player can win with one of their allowed moves.
+    fn can_win_next(&self, position: &Position) -> bool {
+        self.allowed_moves(position) & position.winning_positions() != 0
+    }
+
     /// Resets the solver's state.
+    ///
+    /// If `release_table_on_reset` is set, the transposition table's memory is also released.
+    /// A shared table is never cleared, as other solvers may be using its results.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver};
+    ///
+    ///  let mut solver = Solver::builder().opening_book(BookSource::None).build().unwrap();
+    ///  let positions = [
+    ///      (Position::from_moves("5554224333234511764415115").unwrap(), 4),
+    ///      (Position::from_moves("52753311433677442422121").unwrap(), 8),
+    ///  ];
+    ///
+    ///  // Results from earlier searches never leak into later ones, however often it is reset
+    ///  for i in 0..300 {
+    ///      let (position, score) = &positions[i % 2];
+    ///      solver.reset();
+    ///      assert_eq!(solver.solve(position), *score);
+    ///  }
+    /// ```
     pub fn reset(&mut self) {
         self.explored_positions = 0;
-        self.transposition_table.reset();
+        if self.release_table_on_reset {
+            self.transposition_table.release();
+        } else {
+            self.transposition_table.reset();
+        }
+        for solver in self.root_solvers.iter_mut() {
+            solver.reset();
+        }
     }
 
     /// Solves a position to find its exact score.
@@ -88,44 +373,230 @@ impl Solver {
     /// - A null score if the game will end in a draw
     /// - A negative score if the current player will lose. -1 if the opponent wins with their last
     ///   move, -2 if the opponent wins with their second to last move, ...
+    ///
+    /// The solver's search limits are not applied by this function, so it always finds the
+    /// exact score. To apply the limits, use `try_solve` instead.
     pub fn solve(&mut self, position: &Position) -> i8 {
+        self.search(position, SearchLimits::NONE, None)
+            .expect("A search without limits should always complete.")
+    }
+
+    /// Solves a position to find its exact score, starting from a narrow window around an
+    /// expected score.
+    ///
+    /// Rather than starting its binary search from the full score range, the solver first
+    /// searches the aspiration window `[expected_score - 1, expected_score + 1]`. If the score
+    /// is as expected, this single search proves it. Otherwise, the search fails high or low,
+    /// and the binary search continues over the remaining range. The exact score is therefore
+    /// always found, but it is found fastest when the hint is correct, such as when the score
+    /// of a position is known from an earlier search of its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The board position to solve.
+    /// * `expected_score`: The expected score of the position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver};
+    ///
+    ///  let mut solver = Solver::builder().opening_book(BookSource::None).build().unwrap();
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///
+    ///  // The exact score is found whether or not the hint is correct
+    ///  for hint in [-10, 0, 7, 8, 9, 15] {
+    ///      solver.reset();
+    ///      assert_eq!(solver.solve_with_hint(&pos, hint), 8);
+    ///  }
+    /// ```
+    pub fn solve_with_hint(&mut self, position: &Position, expected_score: i8) -> i8 {
+        self.search(position, SearchLimits::NONE, Some(expected_score))
+            .expect("A search without limits should always complete.")
+    }
+
+    /// Solves a position to find its exact score, within the solver's search limits.
+    ///
+    /// This function behaves in the same way as `solve`, but abandons the search if it exceeds
+    /// any of the limits set in the solver's `limits` field.
+    ///
+    /// # Returns
+    /// The exact score of the position, or `None` if the search was abandoned.
+    pub fn try_solve(&mut self, position: &Position) -> Option<i8> {
+        self.search(position, self.limits, None)
+    }
+
+    /// Searches a position to find its exact score, abandoning the search if any of the
+    /// given limits are exceeded. If an expected score is given, the search starts from an
+    /// aspiration window around it.
+    fn search(&mut self, position: &Position, limits: SearchLimits, expected_score: Option<i8>) -> Option<i8> {
         self.explored_positions = 0;
+        if let Some(statistics) = self.statistics.as_mut() {
+            *statistics = SearchStatistics::new();
+        }
 
         // Before starting the search, checks if the answer is in the opening book
-        if let Some(score) = self.opening_book.as_ref().and_then(|book| book.get(position)) {
-            return score;
+        if let Some(book) = self.book() {
+            let score = book.get(position);
+            if let Some(statistics) = self.statistics.as_mut() {
+                match score {
+                    Some(_) => statistics.book_hits += 1,
+                    None => statistics.book_misses += 1,
+                }
+            }
+            if score.is_some() {
+                return score;
+            }
         }
 
         // Checks if the player can win in one move, as negamax does not support this case
-        if position.can_win_next() {
-            return (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2
+        if self.can_win_next(position) {
+            return Some((Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2)
         }
 
-        // Initial search window is the widest possible score range
-        let mut min = -((Position::BOARD_SIZE - position.get_moves()) as i8) / 2;
-        let mut max = (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2;
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_search_start(position);
+        }
+
+        // The transposition table is only allocated once a search is needed
+        self.prepare_table();
 
-        while min < max {
-            // Binary search for the true score
-            let mut mid = min + (max - min) / 2;
-            if mid <= 0 && min / 2 < mid {
-                mid = min / 2
-            } else if mid >= 0 && max / 2 > mid {
-                mid = max / 2
+        if self.threads <= 1 {
+            let table = Self::search_table(&self.shared_table, &self.transposition_table);
+            let mut search = SearchThread::new(table, self.move_ordering.clone_box(), Self::COLUMNS, None);
+            search.enhanced_cutoffs = self.enhanced_cutoffs;
+            search.move_filter = self.move_filter.as_deref();
+            search.set_limits(limits);
+            search.set_observer(self.observer.as_deref_mut().map(|observer| observer as &mut dyn SearchObserver));
+            search.statistics = self.statistics.as_ref().map(|_| SearchStatistics::new());
+            let score = search.solve(position, expected_score);
+            self.explored_positions = search.explored_positions;
+            if let (Some(statistics), Some(thread_statistics)) = (self.statistics.as_mut(), &search.statistics) {
+                statistics.merge(thread_statistics);
             }
+            score
+        } else {
+            self.solve_parallel(position, limits, expected_score)
+        }
+    }
 
-            // Performs a null-window search to test if the score is greater than the midpoint
-            let score = self.negamax(position, (Position::BOARD_SIZE - position.get_moves()) as u8, mid, mid + 1);
+    /// Tests whether the score of a position is greater than the given threshold, using a
+    /// single null-window search.
+    ///
+    /// The test is always run on a single thread without the solver's observer or statistics,
+    /// and the solver's search limits are not applied.
+    fn is_score_above(&mut self, position: &Position, threshold: i8) -> bool {
+        self.explored_positions = 0;
 
-            // Adjusts the search window based on the result
-            if score <= mid {
-                max = score
-            } else {
-                min = score
+        if let Some(score) = self.book().and_then(|book| book.get(position)) {
+            return score > threshold;
+        }
+
+        // Checks if the player can win in one move, as negamax does not support this case
+        if self.can_win_next(position) {
+            return (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2 > threshold;
+        }
+
+        self.prepare_table();
+
+        let table = Self::search_table(&self.shared_table, &self.transposition_table);
+        let mut search = SearchThread::new(table, self.move_ordering.clone_box(), Self::COLUMNS, None);
+        search.enhanced_cutoffs = self.enhanced_cutoffs;
+        search.move_filter = self.move_filter.as_deref();
+        let depth = (Position::BOARD_SIZE - position.get_moves()) as u8;
+        let score = search.negamax(position, depth, threshold, threshold + 1);
+        self.explored_positions = search.explored_positions;
+        score > threshold
+    }
+
+    /// Solves a position using the Lazy SMP algorithm with the configured number of threads.
+    ///
+    /// Each thread runs the full binary search on the position, sharing its results with the
+    /// other threads through the transposition table. Every thread but the first uses a rotated
+    /// column order to break ties in move ordering, which spreads the threads across different
+    /// parts of the game tree. As all threads compute the same exact score, the first thread to
+    /// complete its search provides the result, and the remaining threads are then stopped.
+    /// If any thread exceeds the search limits, all threads are stopped.
+    fn solve_parallel(&mut self, position: &Position, limits: SearchLimits, expected_score: Option<i8>) -> Option<i8> {
+        let stop = AtomicBool::new(false);
+        let result = OnceLock::new();
+        let explored_positions = AtomicUsize::new(0);
+        let collect_statistics = self.statistics.is_some();
+        let table = Self::search_table(&self.shared_table, &self.transposition_table);
+        let move_filter = self.move_filter.as_deref();
+
+        let new_search = |id: usize| {
+            let mut search = SearchThread::new(
+                table,
+                self.move_ordering.clone_box(),
+                Self::thread_columns(id),
+                Some(&stop),
+            );
+            search.enhanced_cutoffs = self.enhanced_cutoffs;
+            search.move_filter = move_filter;
+            search.statistics = collect_statistics.then(SearchStatistics::new);
+            search.set_limits(limits);
+            search
+        };
+
+        let run_search = |mut search: SearchThread| {
+            let score = search.solve(position, expected_score);
+
+            // Only the first search to complete claims the result, after which all threads stop
+            let completed = score.is_some_and(|score| result.set(score).is_ok());
+            stop.store(true, Ordering::Relaxed);
+            explored_positions.fetch_add(search.explored_positions, Ordering::Relaxed);
+
+            // Iteration times are only kept from the thread which completed its search
+            search.statistics.map(|mut statistics| {
+                if !completed {
+                    statistics.iteration_times.clear();
+                }
+                statistics
+            })
+        };
+
+        let thread_statistics: Vec<Option<SearchStatistics>> = thread::scope(|scope| {
+            let handles: Vec<_> = (1..self.threads)
+                .map(|id| scope.spawn(move || run_search(new_search(id))))
+                .collect();
+
+            // The main search thread runs on the current thread, and reports to the observer
+            let mut main_search = new_search(0);
+            main_search.set_observer(self.observer.as_deref_mut().map(|observer| observer as &mut dyn SearchObserver));
+            let mut thread_statistics = vec![run_search(main_search)];
+            thread_statistics.extend(handles.into_iter().map(|handle| handle.join().unwrap()));
+            thread_statistics
+        });
+
+        if let Some(statistics) = self.statistics.as_mut() {
+            for thread_statistics in thread_statistics.iter().flatten() {
+                statistics.merge(thread_statistics);
             }
         }
 
-        min
+        self.explored_positions = explored_positions.into_inner();
+        result.into_inner()
+    }
+
+    /// Returns the table used by searches, which is the shared table if one is set.
+    fn search_table<'t>(
+        shared_table: &'t Option<SharedTranspositionTable>,
+        transposition_table: &'t TranspositionTable,
+    ) -> &'t dyn TTStore {
+        match shared_table {
+            Some(table) => table,
+            None => transposition_table,
+        }
+    }
+
+    /// Returns the column order used by the search thread with the given ID.
+    ///
+    /// The main thread uses the default centre-first order, while helper threads rotate it.
+    fn thread_columns(id: usize) -> [usize; Position::WIDTH] {
+        let mut columns = Self::COLUMNS;
+        columns.rotate_left(id % Position::WIDTH);
+        columns
     }
 
     /// Calculates the scores for all possible next moves in the given position.
@@ -136,17 +607,359 @@ impl Solver {
     ///
     /// This array can be used to directly calculate the optimal move to play in a position.
     pub fn get_all_move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.move_scores(position, None)
+    }
+
+    /// Calculates the scores for all possible next moves in a position whose score is expected
+    /// to be known, such as from a search on the previous turn.
+    ///
+    /// Each move is solved using `solve_with_hint`, expecting the move to achieve the given
+    /// score. The returned scores are identical to those from `get_all_move_scores`.
+    pub fn get_all_move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        self.move_scores(position, Some(expected_score))
+    }
+
+    /// Calculates the scores for all possible next moves, solving each child position with an
+    /// aspiration window if an expected score for the parent position is given.
+    fn move_scores(&mut self, position: &Position, expected_score: Option<i8>) -> [Option<i8>; Position::WIDTH] {
+        let mut scores = [None; Position::WIDTH];
+
+        // Loops through all columns, calculating and storing their scores if they're playable
+        for (column, child) in self.root_moves(position, &mut scores) {
+            let score = match expected_score {
+                Some(expected_score) => self.solve_with_hint(&child, -expected_score),
+                None => self.solve(&child),
+            };
+            scores[column] = Some(-score);
+        }
+
+        scores
+    }
+
+    /// Calculates the scores for all possible next moves in the given position, evaluating
+    /// each move concurrently.
+    ///
+    /// Moves which can't be scored immediately, either from this solver's opening book or
+    /// because they win the game, are solved in parallel using `rayon`. Each move is solved by
+    /// its own helper solver, which is created with the same replacement policy, opening book,
+    /// move filter, limits and settings as this solver, and is kept between calls to preserve
+    /// its transposition table. Each helper's table has `1 / Position::WIDTH` of the entries of
+    /// this solver's table, so the helpers' tables together use at most as much memory again,
+    /// and are reset or released along with this solver's table. If this solver has a shared
+    /// table, the helpers use it instead of their own tables. The solver's observer receives
+    /// the reports from every helper's search.
+    ///
+    /// The returned scores are identical to those from `get_all_move_scores`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{Position, ReplacementPolicy, Solver};
+    ///
+    ///  let mut solver = Solver::builder()
+    ///      .transposition_table_memory(8)
+    ///      .replacement_policy(ReplacementPolicy::Cluster)
+    ///      .build()
+    ///      .unwrap();
+    ///
+    ///  // Positions from the `end-easy`, `middle-easy` and `middle-medium` test sets
+    ///  for moves in ["7422341735647741166133573473242566", "5554224333234511764415115", "52753311433677442422121", "274552224131661"] {
+    ///      let pos = Position::from_moves(moves).unwrap();
+    ///      let scores = solver.get_all_move_scores(&pos);
+    ///      assert_eq!(solver.get_all_move_scores_parallel(&pos), scores, "wrong scores for {moves}");
+    ///  }
+    /// ```
+    ///
+    /// The solver's observer receives the start of every helper's search:
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, SearchObserver, Solver};
+    ///  use std::sync::Arc;
+    ///  use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    ///  struct SearchCounter(Arc<AtomicUsize>);
+    ///
+    ///  impl SearchObserver for SearchCounter {
+    ///      fn on_search_start(&mut self, _position: &Position) {
+    ///          self.0.fetch_add(1, Ordering::Relaxed);
+    ///      }
+    ///  }
+    ///
+    ///  let searches = Arc::new(AtomicUsize::new(0));
+    ///  let mut solver = Solver::builder()
+    ///      .transposition_table_memory(8)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///  solver.set_observer(SearchCounter(searches.clone()));
+    ///
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///  solver.get_all_move_scores_parallel(&pos);
+    ///  assert_eq!(searches.load(Ordering::Relaxed), pos.possible().count_ones() as usize);
+    ///  assert!(solver.observer.is_some());
+    /// ```
+    pub fn get_all_move_scores_parallel(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.move_scores_parallel(position, None)
+    }
+
+    /// Calculates the scores for all possible next moves in a position whose score is expected
+    /// to be known, evaluating each move concurrently.
+    ///
+    /// This combines `get_all_move_scores_with_hint` and `get_all_move_scores_parallel`, and
+    /// returns scores identical to both.
+    pub fn get_all_move_scores_parallel_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        self.move_scores_parallel(position, Some(expected_score))
+    }
+
+    /// Calculates the scores for all possible next moves concurrently, solving each child
+    /// position with an aspiration window if an expected score for the parent is given.
+    fn move_scores_parallel(&mut self, position: &Position, expected_score: Option<i8>) -> [Option<i8>; Position::WIDTH] {
         let mut scores = [None; Position::WIDTH];
+
+        // Scores moves found in the opening book directly, collecting the rest to be solved
+        let children: Vec<(usize, Position)> = self.root_moves(position, &mut scores)
+            .into_iter()
+            .filter(|(column, child)| {
+                match self.book().and_then(|book| book.get(child)) {
+                    Some(score) => { scores[*column] = Some(-score); false },
+                    None => true,
+                }
+            })
+            .collect();
+
+        // Solves the remaining moves in parallel, each with its own helper solver, with the
+        // solver's observer shared between the helpers for the duration of the searches
+        let observer = self.observer.take().map(|observer| Arc::new(Mutex::new(observer)));
+        let root_solvers = self.root_solvers(children.len());
+        for solver in root_solvers.iter_mut() {
+            solver.observer = observer.clone()
+                .map(|observer| Box::new(SharedObserver::new(observer)) as Box<dyn SearchObserver>);
+        }
+        let results: Vec<(usize, i8, usize)> = children
+            .par_iter()
+            .zip(root_solvers.par_iter_mut())
+            .map(|((column, child), solver)| {
+                let score = match expected_score {
+                    Some(expected_score) => solver.solve_with_hint(child, -expected_score),
+                    None => solver.solve(child),
+                };
+                (*column, score, solver.explored_positions)
+            })
+            .collect();
+
+        for solver in self.root_solvers.iter_mut() {
+            solver.observer = None;
+        }
+        self.observer = observer.map(|observer| {
+            Arc::into_inner(observer)
+                .expect("The helpers' observers should have been dropped.")
+                .into_inner()
+                .unwrap()
+        });
+
+        self.explored_positions = 0;
+        for (column, score, explored_positions) in results {
+            scores[column] = Some(-score);
+            self.explored_positions += explored_positions;
+        }
+
+        scores
+    }
+
+    /// Returns at least the given number of helper solvers for scoring moves in parallel,
+    /// creating them from this solver's configuration and updating their settings to match it.
+    fn root_solvers(&mut self, count: usize) -> &mut [Solver] {
+        // The helpers' tables share this solver's memory budget between them, and helpers with
+        // a different table size, policy or opening book are replaced
+        let policy = self.transposition_table.policy();
+        let table_size = TranspositionTable::with_size_and_policy(
+            self.transposition_table.size() / Position::WIDTH,
+            policy,
+        ).size();
+        let has_book = self.opening_book.is_some();
+        self.root_solvers.retain(|solver| {
+            solver.transposition_table.size() == table_size
+                && solver.transposition_table.policy() == policy
+                && solver.opening_book.is_some() == has_book
+        });
+
+        while self.root_solvers.len() < count {
+            let solver = Solver {
+                transposition_table: TranspositionTable::with_size_and_policy(table_size, policy),
+                opening_book: self.opening_book.clone(),
+                ..Solver::empty()
+            };
+            self.root_solvers.push(solver);
+        }
+
+        for solver in self.root_solvers.iter_mut() {
+            solver.shared_table = self.shared_table.clone();
+            solver.move_ordering = self.move_ordering.clone_box();
+            solver.enhanced_cutoffs = self.enhanced_cutoffs;
+            solver.limits = self.limits;
+            solver.release_table_on_reset = self.release_table_on_reset;
+            solver.replace_move_filter(self.move_filter.clone());
+        }
+        &mut self.root_solvers[..count]
+    }
+
+    /// Analyses the best moves in a position, returning up to `k` of them ranked from best to
+    /// worst.
+    ///
+    /// Each move is given with its exact score, its outcome and its principal variation: the
+    /// line of perfect play which follows it until the end of the game. As scores reflect how
+    /// quickly the game is won or lost, ranking moves by score prefers the fastest wins and
+    /// the slowest losses. Moves with equal scores are ranked by how close they are to the
+    /// centre.
+    ///
+    /// Assumes that the given position is valid and not won by either player.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{Outcome, Position, Solver};
+    ///
+    ///  let mut solver = Solver::new();
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///
+    ///  let analysis = solver.analyse(&pos, 2);
+    ///  assert_eq!(analysis.len(), 2);
+    ///  assert_eq!((analysis[0].column, analysis[0].score, analysis[0].outcome), (4, 8, Outcome::Win));
+    ///  assert_eq!(analysis[0].principal_variation_string(), "55342");
+    ///
+    ///  // Moves with equal scores are ranked from the centre outwards
+    ///  assert_eq!((analysis[1].column, analysis[1].score), (3, 7));
+    ///  assert_eq!(analysis[1].principal_variation_string(), "4455335");
+    ///
+    ///  // The principal variation can be appended to the position's moves
+    ///  let line = format!("52753311433677442422121{}", analysis[1].principal_variation_string());
+    ///  assert!(Position::from_moves(&line[..line.len() - 1]).unwrap().is_winning_move(4));
+    /// ```
+    pub fn analyse(&mut self, position: &Position, k: usize) -> Vec<MoveAnalysis> {
+        let scores = self.get_all_move_scores(position);
+
+        // Sorts the moves by score, keeping the centre-first order of moves with equal scores
+        let mut moves: Vec<(usize, i8)> = Self::COLUMNS
+            .iter()
+            .filter_map(|&column| scores[column].map(|score| (column, score)))
+            .collect();
+        moves.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        moves.truncate(k);
+
+        moves.into_iter()
+            .map(|(column, score)| {
+                let mut principal_variation = vec![column];
+                if !position.is_winning_move(column) {
+                    let mut child = *position;
+                    child.play(column);
+                    principal_variation.extend(self.best_line(&child, -score));
+                }
+                MoveAnalysis { column, score, outcome: Outcome::from_score(score), principal_variation }
+            })
+            .collect()
+    }
+
+    /// Finds the opponent's refutation of a losing move, returning the line as a string of
+    /// 1-based columns starting with the move itself.
+    ///
+    /// The line is the shortest forced win for the opponent after the move, with the losing
+    /// player delaying the loss for as long as possible, and ends with the opponent's winning
+    /// move. It can be appended to the move sequence of the given position and parsed with
+    /// `Position::from_moves`.
+    ///
+    /// Returns `None` if the move can't be played, or if it doesn't lose against perfect play.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{Position, Solver};
+    ///
+    ///  let mut solver = Solver::new();
+    ///  let moves = "7422341735647741166133573473242566";
+    ///  let pos = Position::from_moves(moves).unwrap();
+    ///
+    ///  // Playing in the 1st column lets the opponent force a win with their second move
+    ///  assert_eq!(solver.refutation(&pos, 0), Some("1155".to_string()));
+    ///  let line = format!("{moves}1155");
+    ///  assert!(Position::from_moves(&line[..line.len() - 1]).unwrap().is_winning_move(4));
+    ///
+    ///  // Moves which don't lose have no refutation
+    ///  assert_eq!(solver.refutation(&pos, 1), None);
+    /// ```
+    pub fn refutation(&mut self, position: &Position, column: usize) -> Option<String> {
+        if column >= Position::WIDTH
+            || self.allowed_moves(position) & Position::column_mask(column) == 0
+            || position.is_winning_move(column)
+        {
+            return None;
+        }
+
+        let mut child = *position;
+        child.play(column);
+        let score = self.solve(&child);
+        if score <= 0 {
+            return None;
+        }
+
+        let line = std::iter::once(column).chain(self.best_line(&child, score));
+        Some(line.map(|column| (column + 1).to_string()).collect())
+    }
+
+    /// Finds a line of perfect play from a position with a known score until the end of the
+    /// game, returning the columns played.
+    ///
+    /// At each step, the first move in centre-first order which achieves the position's score
+    /// is played. Each move is checked with a single null-window search, as no move can
+    /// achieve a higher score.
+    fn best_line(&mut self, position: &Position, score: i8) -> Vec<usize> {
+        let mut line = Vec::new();
+        let mut position = *position;
+        let mut score = score;
+
+        while position.get_moves() < Position::BOARD_SIZE {
+            let win_score = (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2;
+            let allowed_moves = self.allowed_moves(&position);
+            let column = Self::COLUMNS
+                .iter()
+                .copied()
+                .filter(|&column| allowed_moves & Position::column_mask(column) != 0)
+                .find(|&column| {
+                    if position.is_winning_move(column) {
+                        return win_score == score;
+                    }
+                    let mut child = position;
+                    child.play(column);
+                    !self.is_score_above(&child, -score)
+                })
+                .expect("A move should achieve the position's score.");
+
+            line.push(column);
+            if position.is_winning_move(column) {
+                break;
+            }
+            position.play(column);
+            score = -score;
+        }
+
+        line
+    }
+
+    /// Helper function to find the moves which must be searched to score a position's children.
+    ///
+    /// Immediately winning moves are scored directly in the given array, and all other allowed
+    /// moves are returned along with their resulting positions.
+    fn root_moves(&self, position: &Position, scores: &mut [Option<i8>; Position::WIDTH]) -> Vec<(usize, Position)> {
+        let mut children = Vec::with_capacity(Position::WIDTH);
         let depth = (Position::BOARD_SIZE - position.get_moves()) as u8;
 
         // If the game is won or the position is full, no moves are possible
         if position.is_won_position() || depth == 0 {
-            return scores;
+            return children;
         }
 
-        // Loops through all columns, calculating and storing their scores if they're playable
+        let allowed_moves = self.allowed_moves(position);
         for &column in Self::COLUMNS.iter() {
-            if !position.is_playable(column) {
+            if allowed_moves & Position::column_mask(column) == 0 {
                 continue;
             }
 
@@ -155,26 +968,298 @@ impl Solver {
             } else {
                 let mut new_position = *position;
                 new_position.play(column);
-                scores[column] = Some(-self.solve(&new_position));
+                children.push((column, new_position));
             }
         }
 
-        scores
+        children
+    }
+}
+
+/// Uses the solver's exact scores to evaluate positions, making the solver a perfect engine.
+impl Engine for Solver {
+    fn name(&self) -> &str {
+        "Solver"
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        self.solve(position)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        if self.parallel_move_scores {
+            self.get_all_move_scores_parallel(position)
+        } else {
+            self.get_all_move_scores(position)
+        }
+    }
+
+    fn move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        if self.parallel_move_scores {
+            self.get_all_move_scores_parallel_with_hint(position, expected_score)
+        } else {
+            self.get_all_move_scores_with_hint(position, expected_score)
+        }
+    }
+
+    fn reset(&mut self) {
+        Solver::reset(self)
+    }
+}
+
+/// Proves outcomes using null-window searches, which are cheaper than finding exact scores.
+impl OutcomeProver for Solver {
+    fn is_win(&mut self, position: &Position) -> bool {
+        self.is_score_above(position, 0)
+    }
+
+    fn is_loss(&mut self, position: &Position) -> bool {
+        !self.is_score_above(position, -1)
+    }
+}
+
+/// The state of a single thread searching a position.
+///
+/// This holds everything the negamax search needs to mutate, so that several threads can
+/// search the same position at once while sharing a single transposition table.
+struct SearchThread<'a> {
+    /// The transposition table shared by all threads of the search.
+    transposition_table: &'a dyn TTStore,
+
+    /// This thread's copy of the strategy used to order moves.
+    move_ordering: Box<dyn MoveOrdering>,
+
+    /// Whether the search uses enhanced transposition cut-offs.
+    enhanced_cutoffs: bool,
+
+    /// An optional restriction on the moves which may be played, which switches the search
+    /// to `restricted_negamax`.
+    move_filter: Option<&'a dyn MoveFilter>,
+
+    /// The order in which columns are added to the move sorter, used to break ties.
+    columns: [usize; Position::WIDTH],
+
+    /// An optional flag which signals that the search should be abandoned.
+    stop: Option<&'a AtomicBool>,
+
+    /// A counter for the number of nodes explored by this thread.
+    explored_positions: usize,
+
+    /// Detailed statistics for this thread's search, which are only collected if enabled.
+    statistics: Option<SearchStatistics>,
+
+    /// An optional observer which receives progress reports from this thread.
+    observer: Option<&'a mut dyn SearchObserver>,
+
+    /// The number of explored nodes at which the observer should next receive a report.
+    next_report: usize,
+
+    /// The current progress of the search, which is reported to the observer.
+    progress: SearchProgress,
+
+    /// The time at which the observed search started, if an observer is attached.
+    start_time: Option<Instant>,
+
+    /// The limits which this thread's search must stay within.
+    limits: SearchLimits,
+
+    /// The time at which the search must be abandoned, if it has a time limit.
+    deadline: Option<Instant>,
+
+    /// The number of explored nodes at which the search limits should next be checked.
+    next_limit_check: usize,
+
+    /// The number of explored nodes at which the next report or limit check is due.
+    next_checkpoint: usize,
+
+    /// Whether the search has been abandoned for exceeding its limits.
+    aborted: bool,
+}
+
+impl<'a> SearchThread<'a> {
+    /// Creates a new search thread state.
+    fn new(
+        transposition_table: &'a dyn TTStore,
+        move_ordering: Box<dyn MoveOrdering>,
+        columns: [usize; Position::WIDTH],
+        stop: Option<&'a AtomicBool>
+    ) -> SearchThread<'a> {
+        SearchThread {
+            transposition_table,
+            move_ordering,
+            enhanced_cutoffs: false,
+            move_filter: None,
+            columns,
+            stop,
+            explored_positions: 0,
+            statistics: None,
+            observer: None,
+            next_report: usize::MAX,
+            progress: SearchProgress::default(),
+            start_time: None,
+            limits: SearchLimits::NONE,
+            deadline: None,
+            next_limit_check: usize::MAX,
+            next_checkpoint: usize::MAX,
+            aborted: false,
+        }
+    }
+
+    /// The minimum number of remaining moves for which enhanced transposition cut-offs are
+    /// used, as probing every child is too costly near the leaves of the search.
+    const ENHANCED_CUTOFF_DEPTH: u8 = 12;
+
+    /// The number of nodes explored between checks of the search's time limit.
+    const TIME_CHECK_INTERVAL: usize = 1 << 12;
+
+    /// Sets the observer which receives progress reports from this thread.
+    fn set_observer(&mut self, observer: Option<&'a mut dyn SearchObserver>) {
+        self.next_report = observer.as_ref().map_or(usize::MAX, |observer| observer.node_interval().max(1));
+        self.start_time = observer.is_some().then(Instant::now);
+        self.observer = observer;
+        self.next_checkpoint = self.next_report.min(self.next_limit_check);
+    }
+
+    /// Sets the limits which this thread's search must stay within.
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+        self.deadline = limits.max_time.map(|max_time| Instant::now() + max_time);
+        self.next_limit_check = self.limit_check_after(0);
+        self.next_checkpoint = self.next_report.min(self.next_limit_check);
+    }
+
+    /// Calculates the number of explored nodes at which the limits should next be checked.
+    fn limit_check_after(&self, explored_positions: usize) -> usize {
+        let node_limit = self.limits.max_nodes.map_or(usize::MAX, |max_nodes| max_nodes.saturating_add(1));
+        if self.deadline.is_some() {
+            node_limit.min(explored_positions + Self::TIME_CHECK_INTERVAL)
+        } else {
+            node_limit
+        }
+    }
+
+    /// Reports progress to the observer and checks the search limits, if either is due.
+    #[cold]
+    fn checkpoint(&mut self) {
+        if self.explored_positions >= self.next_report {
+            if let Some(observer) = self.observer.as_mut() {
+                self.progress.explored_positions = self.explored_positions;
+                self.progress.elapsed = self.start_time.map_or(Duration::ZERO, |start_time| start_time.elapsed());
+                observer.on_nodes(&self.progress);
+                self.next_report += observer.node_interval().max(1);
+            }
+        }
+
+        if self.explored_positions >= self.next_limit_check {
+            let node_limit_exceeded = self.limits.max_nodes.is_some_and(|max_nodes| self.explored_positions > max_nodes);
+            let time_limit_exceeded = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            self.aborted |= node_limit_exceeded || time_limit_exceeded;
+            self.next_limit_check = self.limit_check_after(self.explored_positions);
+        }
+
+        self.next_checkpoint = self.next_report.min(self.next_limit_check);
+    }
+
+    /// Indicates whether the search has exceeded its limits or been stopped by another thread.
+    #[inline(always)]
+    fn is_stopped(&self) -> bool {
+        self.aborted || self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Finds the exact score of a position using a binary search with null-window searches.
+    ///
+    /// If an expected score is given, the first search uses an aspiration window around it,
+    /// and the binary search continues over the remaining range if the score lies outside it.
+    ///
+    /// Assumes that the position is not won by either player and that the current player
+    /// cannot win with their next move. Returns `None` if the search was stopped.
+    fn solve(&mut self, position: &Position, expected_score: Option<i8>) -> Option<i8> {
+        // Initial search window is the widest possible score range
+        let mut min = -((Position::BOARD_SIZE - position.get_moves()) as i8) / 2;
+        let mut max = (Position::BOARD_SIZE + 1 - position.get_moves()) as i8 / 2;
+        let mut expected_score = expected_score.map(|score| score.clamp(min, max));
+
+        while min < max && !self.is_stopped() {
+            let (mid, alpha, beta) = match expected_score.take() {
+                // Aspiration window around the expected score
+                Some(score) => (score, (score - 1).max(min), (score + 1).min(max)),
+
+                // Binary search for the true score
+                None => {
+                    let mut mid = min + (max - min) / 2;
+                    if mid <= 0 && min / 2 < mid {
+                        mid = min / 2
+                    } else if mid >= 0 && max / 2 > mid {
+                        mid = max / 2
+                    }
+                    (mid, mid, mid + 1)
+                }
+            };
+
+            // Reports the new search window to the observer
+            if let Some(observer) = self.observer.as_mut() {
+                self.progress = SearchProgress {
+                    iteration: self.progress.iteration + 1,
+                    min,
+                    max,
+                    mid,
+                    explored_positions: self.explored_positions,
+                    elapsed: self.start_time.map_or(Duration::ZERO, |start_time| start_time.elapsed()),
+                };
+                observer.on_iteration(&self.progress);
+            }
+
+            // Performs a null-window search to test if the score is greater than the midpoint,
+            // or an aspiration search to test if the score is the expected score
+            let start_time = self.statistics.is_some().then(Instant::now);
+            let score = self.negamax(position, (Position::BOARD_SIZE - position.get_moves()) as u8, alpha, beta);
+            if let (Some(statistics), Some(start_time)) = (self.statistics.as_mut(), start_time) {
+                statistics.iteration_times.push(start_time.elapsed());
+            }
+
+            // Adjusts the search window based on the result
+            if score <= alpha {
+                max = score
+            } else if score >= beta {
+                min = score
+            } else {
+                (min, max) = (score, score)
+            }
+        }
+
+        (!self.is_stopped()).then_some(min)
     }
 
     /// The core negamax search function with alpha-beta pruning.
     fn negamax(&mut self, position: &Position, depth: u8, mut alpha: i8, mut beta: i8) -> i8 {
         self.explored_positions += 1;
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.nodes_per_depth[depth as usize] += 1;
+        }
+        if self.explored_positions >= self.next_checkpoint {
+            self.checkpoint();
+        }
 
         // Checks for a drawn game
         if depth == 0 {
             return 0;
         }
 
+        if let Some(filter) = self.move_filter {
+            return self.restricted_negamax(position, depth, alpha, beta, filter);
+        }
+
         // Transposition table look-up
         let original_alpha = alpha;
-        let key = position.get_key();
-        if let Some(entry) = self.transposition_table.get(key) {
+        let (key, mirrored) = position.get_canonical_key();
+        let orient = |column| if mirrored { Position::mirror_column(column) } else { column };
+        let entry = self.transposition_table.get(key);
+        let tt_move = entry.and_then(|entry| entry.best_move).map(orient);
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.tt_probes += 1;
+            statistics.tt_hits += entry.is_some() as usize;
+        }
+        if let Some(entry) = entry {
             if entry.depth >= depth {
                 match entry.flag {
                     TTFlag::Exact => return entry.value,
@@ -206,26 +1291,74 @@ impl Solver {
             beta = max;
         }
 
-        // Scores and sorts possible moves to explore the best ones first
-        let mut moves = MoveSorter::new();
-        for &column in Self::COLUMNS.iter().rev() {
-            let move_bit = possible_moves & Position::column_mask(column);
-            if move_bit > 0 {
-                moves.add(column, position.score_move(move_bit))
+        // Enhanced transposition cut-offs, which use the children's entries to bound the score.
+        // Forced moves are skipped, as their only child is probed when it is searched.
+        if self.enhanced_cutoffs && depth >= Self::ENHANCED_CUTOFF_DEPTH && !possible_moves.is_power_of_two() {
+            let mut upper = min;
+            for column in 0..Position::WIDTH {
+                if possible_moves & Position::column_mask(column) == 0 {
+                    continue;
+                }
+                let mut child = *position;
+                child.play(column);
+
+                // Converts bounds on the child's score into bounds on the score of this move
+                let (move_lower, move_upper) = match self.transposition_table.get(child.get_key()) {
+                    Some(entry) => match entry.flag {
+                        TTFlag::Exact => (-entry.value, -entry.value),
+                        TTFlag::UpperBound => (-entry.value, max),
+                        TTFlag::LowerBound => (min, -entry.value),
+                    },
+                    None => (min, max),
+                };
+
+                // A single move scoring at least beta causes a cut-off
+                if move_lower >= beta {
+                    if let Some(statistics) = self.statistics.as_mut() {
+                        statistics.enhanced_cutoffs += 1;
+                    }
+                    return move_lower;
+                }
+                alpha = alpha.max(move_lower);
+                upper = upper.max(move_upper);
+            }
+
+            // If no move can score above alpha, the node fails low without being searched
+            if upper <= alpha {
+                if let Some(statistics) = self.statistics.as_mut() {
+                    statistics.enhanced_cutoffs += 1;
+                }
+                return upper;
             }
+            beta = beta.min(upper);
         }
 
+        // Sorts possible moves to explore the best ones first
+        let moves = self.move_ordering.order_moves(position, possible_moves, &self.columns, tt_move);
+
         // Computes the scores of all possible next moves, keeping the best
-        for column in moves {
+        let mut best_move = None;
+        for (index, column) in moves.enumerate() {
             let mut new_position = *position;
             new_position.play(column);
             let score = -self.negamax(&new_position, depth - 1, -beta, -alpha);
+
+            // Abandons the search without storing a result if it has been stopped
+            if self.is_stopped() {
+                return alpha;
+            }
+
             if score > alpha {
                 alpha = score;
+                best_move = Some(column);
             }
 
             // Stops searching if a score is found outside the search window
             if alpha >= beta {
+                if let Some(statistics) = self.statistics.as_mut() {
+                    statistics.cutoffs[index] += 1;
+                }
+                self.move_ordering.on_cutoff(position, column);
                 break;
             }
         }
@@ -238,7 +1371,102 @@ impl Solver {
         } else {
             TTFlag::Exact
         };
-        self.transposition_table.put(key, alpha, flag, depth);
+
+        // Best moves are stored in the orientation of the position's key
+        self.transposition_table.put(key, alpha, flag, depth, best_move.map(orient));
+
+        alpha
+    }
+
+    /// The negamax search used when the moves which may be played are restricted by a filter.
+    ///
+    /// A restriction can stop a player from completing an alignment or blocking one of their
+    /// opponent's, so unlike the unrestricted search, this can't discard the moves which let
+    /// the opponent win immediately. Instead, each node checks whether any of the current
+    /// player's allowed moves wins. Positions are keyed by `get_unmirrored_key` unless the
+    /// filter is symmetric, and enhanced transposition cut-offs are not used.
+    fn restricted_negamax(&mut self, position: &Position, depth: u8, mut alpha: i8, mut beta: i8, filter: &dyn MoveFilter) -> i8 {
+        let moves_played = position.get_moves();
+        let allowed_moves = allowed_moves(filter, position);
+
+        // The current player wins with their next move
+        if allowed_moves & position.winning_positions() != 0 {
+            return (Position::BOARD_SIZE + 1 - moves_played) as i8 / 2;
+        }
+
+        // Transposition table look-up
+        let original_alpha = alpha;
+        let (key, mirrored) = if filter.is_symmetric() {
+            position.get_canonical_key()
+        } else {
+            (position.get_unmirrored_key(), false)
+        };
+        let orient = |column| if mirrored { Position::mirror_column(column) } else { column };
+        let entry = self.transposition_table.get(key);
+        let tt_move = entry.and_then(|entry| entry.best_move).map(orient);
+        if let Some(statistics) = self.statistics.as_mut() {
+            statistics.tt_probes += 1;
+            statistics.tt_hits += entry.is_some() as usize;
+        }
+        if let Some(entry) = entry {
+            if entry.depth >= depth {
+                match entry.flag {
+                    TTFlag::Exact => return entry.value,
+                    TTFlag::LowerBound if entry.value >= beta => return entry.value,
+                    TTFlag::UpperBound if entry.value <= alpha => return entry.value,
+                    _ => {} // Can't use the entry, so continue the search.
+                }
+            }
+        }
+
+        // Tightens the upper bound as we cannot win immediately
+        let max = ((Position::BOARD_SIZE - moves_played) as i8 - 1) / 2;
+        if beta > max {
+            if alpha >= max { return max }
+            beta = max;
+        }
+
+        // Sorts the allowed moves to explore the best ones first
+        let moves = self.move_ordering.order_moves(position, allowed_moves, &self.columns, tt_move);
+
+        // Computes the scores of all allowed next moves, keeping the best
+        let mut best_move = None;
+        for (index, column) in moves.enumerate() {
+            let mut new_position = *position;
+            new_position.play(column);
+            let score = -self.negamax(&new_position, depth - 1, -beta, -alpha);
+
+            // Abandons the search without storing a result if it has been stopped
+            if self.is_stopped() {
+                return alpha;
+            }
+
+            if score > alpha {
+                alpha = score;
+                best_move = Some(column);
+            }
+
+            // Stops searching if a score is found outside the search window
+            if alpha >= beta {
+                if let Some(statistics) = self.statistics.as_mut() {
+                    statistics.cutoffs[index] += 1;
+                }
+                self.move_ordering.on_cutoff(position, column);
+                break;
+            }
+        }
+
+        // Stores the result of this search to the transposition table
+        let flag = if alpha <= original_alpha {
+            TTFlag::UpperBound
+        } else if alpha >= beta {
+            TTFlag::LowerBound
+        } else {
+            TTFlag::Exact
+        };
+
+        // Best moves are stored in the orientation of the position's key
+        self.transposition_table.put(key, alpha, flag, depth, best_move.map(orient));
 
         alpha
     }
@@ -249,8 +1477,19 @@ impl Default for Solver {
     fn default() -> Solver {
         Solver {
             explored_positions: 0,
+            threads: 1,
             transposition_table: TranspositionTable::new(),
-            opening_book: OpeningBook::from_static_bytes(OPENING_BOOK_BYTES).ok()
+            shared_table: None,
+            move_ordering: Box::new(ThreatOrdering),
+            enhanced_cutoffs: true,
+            opening_book: OpeningBook::from_static_bytes(OPENING_BOOK_BYTES).ok(),
+            statistics: None,
+            observer: None,
+            limits: SearchLimits::NONE,
+            release_table_on_reset: false,
+            parallel_move_scores: false,
+            move_filter: None,
+            root_solvers: Vec::new(),
         }
     }
 }
\ No newline at end of file
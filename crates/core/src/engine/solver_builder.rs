@@ -0,0 +1,310 @@
+//! A builder for configuring and creating `Solver` instances.
+
+use super::solver::OPENING_BOOK_BYTES;
+use crate::{
+    MoveFilter,
+    MoveOrdering,
+    OpeningBook,
+    ReplacementPolicy,
+    SearchLimits,
+    SearchObserver,
+    SharedTranspositionTable,
+    Solver,
+    ThreatOrdering,
+    TranspositionTable
+};
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The source from which a solver's opening book is loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BookSource {
+    /// No opening book is used.
+    None,
+    /// The default opening book of depth 8, which is embedded within the executable.
+    #[default]
+    Embedded,
+    /// An opening book loaded from a binary file at the given path.
+    File(PathBuf),
+    /// An opening book deserialised from the given bytes.
+    Bytes(Vec<u8>),
+}
+
+/// The ways in which the size of a solver's transposition table can be specified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TableSize {
+    /// A minimum number of entries.
+    Entries(usize),
+    /// A memory budget in bytes.
+    Memory(usize),
+}
+
+/// A builder for configuring and creating `Solver` instances.
+///
+/// By default, the builder creates a solver identical to `Solver::new()`: a single-threaded
+/// solver with the embedded opening book, a transposition table of
+/// `TranspositionTable::MAX_SIZE` entries and no search limits.
+#[derive(Debug, Default)]
+pub struct SolverBuilder {
+    transposition_table_size: Option<TableSize>,
+    replacement_policy: ReplacementPolicy,
+    shared_table: Option<SharedTranspositionTable>,
+    table_file: Option<PathBuf>,
+    book_source: BookSource,
+    threads: Option<usize>,
+    limits: SearchLimits,
+    observer: Option<Box<dyn SearchObserver>>,
+    move_ordering: Option<Box<dyn MoveOrdering>>,
+    disable_enhanced_cutoffs: bool,
+    release_table_on_reset: bool,
+    parallel_move_scores: bool,
+    move_filter: Option<Arc<dyn MoveFilter>>,
+}
+
+impl SolverBuilder {
+    /// Creates a new builder with the default solver configuration.
+    pub fn new() -> SolverBuilder {
+        Self::default()
+    }
+
+    /// Sets the number of entries in the solver's transposition table.
+    ///
+    /// The size is rounded up to the nearest prime number, and is never less than
+    /// `TranspositionTable::MIN_SIZE`. See `TranspositionTable::with_size` for details.
+    pub fn transposition_table_size(mut self, entries: usize) -> SolverBuilder {
+        self.transposition_table_size = Some(TableSize::Entries(entries));
+        self
+    }
+
+    /// Sets the memory budget for the solver's transposition table in megabytes.
+    ///
+    /// The table uses the largest prime number of buckets which fits within the budget, with
+    /// a minimum of `TranspositionTable::MIN_SIZE` buckets. Each bucket holds
+    /// `ReplacementPolicy::bucket_size` entries of 8 bytes, so the smallest table takes about
+    /// 1 MB with `AlwaysReplace`, 2 MB with `TwoTier` and 4 MB with `Cluster`, and smaller
+    /// budgets are exceeded. Budgets larger than the address space are clamped to the largest possible
+    /// allocation. See `TranspositionTable::with_memory` for details.
+    pub fn transposition_table_memory(mut self, megabytes: usize) -> SolverBuilder {
+        let bytes = megabytes.saturating_mul(1 << 20).min(isize::MAX as usize);
+        self.transposition_table_size = Some(TableSize::Memory(bytes));
+        self
+    }
+
+    /// Sets the replacement policy used by the solver's transposition table.
+    ///
+    /// The table uses the same amount of memory with every policy.
+    pub fn replacement_policy(mut self, policy: ReplacementPolicy) -> SolverBuilder {
+        self.replacement_policy = policy;
+        self
+    }
+
+    /// Sets a file containing a transposition table saved with `TranspositionTable::save`,
+    /// which the solver's table is loaded from to warm-start its searches. The loaded table
+    /// keeps its saved size and replacement policy.
+    pub fn transposition_table_file(mut self, path: impl Into<PathBuf>) -> SolverBuilder {
+        self.table_file = Some(path.into());
+        self
+    }
+
+    /// Sets a transposition table shared with other solvers, which the solver uses instead of
+    /// its own table. The solver's own table is then never allocated.
+    pub fn shared_table(mut self, table: SharedTranspositionTable) -> SolverBuilder {
+        self.shared_table = Some(table);
+        self
+    }
+
+    /// Sets the source from which the solver's opening book is loaded.
+    pub fn opening_book(mut self, book_source: BookSource) -> SolverBuilder {
+        self.book_source = book_source;
+        self
+    }
+
+    /// Sets the number of threads the solver uses to search each position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, SearchLimits, Solver};
+    ///
+    ///  let mut solver = Solver::builder()
+    ///      .threads(4)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///
+    ///  // Positions from the `end-easy`, `middle-easy` and `middle-medium` test sets
+    ///  let positions = [
+    ///      ("2252576253462244111563365343671351441", -1),
+    ///      ("7422341735647741166133573473242566", 1),
+    ///      ("5554224333234511764415115", 4),
+    ///      ("52753311433677442422121", 8),
+    ///      ("274552224131661", 0),
+    ///      ("5455174361263362", -1),
+    ///  ];
+    ///  for (moves, score) in positions {
+    ///      let pos = Position::from_moves(moves).unwrap();
+    ///      solver.reset();
+    ///      assert_eq!(solver.solve(&pos), score, "wrong score for {moves}");
+    ///  }
+    ///
+    ///  // A thread exceeding its limits stops every thread, without storing a result
+    ///  let pos = Position::from_moves("274552224131661").unwrap();
+    ///  solver.reset();
+    ///  solver.limits = SearchLimits::new().with_max_nodes(1000);
+    ///  assert_eq!(solver.try_solve(&pos), None);
+    ///  assert_eq!(solver.try_solve(&pos), None);
+    ///
+    ///  // Searches started after an abandoned search still find the exact score
+    ///  assert_eq!(solver.solve(&pos), 0);
+    /// ```
+    pub fn threads(mut self, threads: usize) -> SolverBuilder {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// Sets the limits which are applied to the solver's searches by `Solver::try_solve`.
+    pub fn limits(mut self, limits: SearchLimits) -> SolverBuilder {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets an observer which receives progress reports during each of the solver's searches.
+    pub fn observer(mut self, observer: impl SearchObserver + 'static) -> SolverBuilder {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Sets the strategy used to order the moves explored at each node of the search.
+    pub fn move_ordering(mut self, move_ordering: impl MoveOrdering + 'static) -> SolverBuilder {
+        self.move_ordering = Some(Box::new(move_ordering));
+        self
+    }
+
+    /// Sets whether the solver uses enhanced transposition cut-offs, which are enabled by
+    /// default. See `Solver::enhanced_cutoffs` for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver};
+    ///
+    ///  let new_solver = |enabled| Solver::builder()
+    ///      .transposition_table_memory(16)
+    ///      .opening_book(BookSource::None)
+    ///      .enhanced_cutoffs(enabled)
+    ///      .build()
+    ///      .unwrap();
+    ///  let (mut with_cutoffs, mut without_cutoffs) = (new_solver(true), new_solver(false));
+    ///
+    ///  // Positions from the `middle-easy` and `middle-medium` test sets
+    ///  let positions = [
+    ///      ("52753311433677442422121", 8),
+    ///      ("274552224131661", 0),
+    ///      ("5455174361263362", -1),
+    ///  ];
+    ///  let (mut nodes_with, mut nodes_without) = (0, 0);
+    ///  for (moves, score) in positions {
+    ///      let pos = Position::from_moves(moves).unwrap();
+    ///      with_cutoffs.reset();
+    ///      without_cutoffs.reset();
+    ///      assert_eq!(with_cutoffs.solve(&pos), score);
+    ///      assert_eq!(without_cutoffs.solve(&pos), score);
+    ///      nodes_with += with_cutoffs.explored_positions;
+    ///      nodes_without += without_cutoffs.explored_positions;
+    ///  }
+    ///  assert!(nodes_with < nodes_without);
+    /// ```
+    pub fn enhanced_cutoffs(mut self, enabled: bool) -> SolverBuilder {
+        self.disable_enhanced_cutoffs = !enabled;
+        self
+    }
+
+    /// Sets whether the solver releases its transposition table's memory when it is reset.
+    ///
+    /// See `Solver::release_table_on_reset` for details.
+    pub fn release_table_on_reset(mut self, release: bool) -> SolverBuilder {
+        self.release_table_on_reset = release;
+        self
+    }
+
+    /// Sets whether the solver's `Engine` implementation evaluates each possible move
+    /// concurrently. See `Solver::parallel_move_scores` for details.
+    pub fn parallel_move_scores(mut self, parallel: bool) -> SolverBuilder {
+        self.parallel_move_scores = parallel;
+        self
+    }
+
+    /// Sets a filter restricting the moves which may be played during the solver's searches.
+    /// See `Solver::set_move_filter` for details.
+    pub fn move_filter(mut self, filter: impl MoveFilter + 'static) -> SolverBuilder {
+        self.move_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Creates a `Solver` instance with the configured settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the opening book could not be loaded from the given source, if
+    /// the transposition table file could not be loaded, or if the file holds results from
+    /// searches restricted by a move filter and no move filter is set.
+    ///
+    /// Also returns an error if conflicting transposition table options are given: a table
+    /// file can't be combined with a table size or memory budget, and a shared table can't be
+    /// combined with any of the solver's own table options, as they would be ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, SharedTranspositionTable, Solver};
+    ///
+    ///  let table = SharedTranspositionTable::with_memory(1 << 20);
+    ///  assert!(Solver::builder().shared_table(table.clone()).opening_book(BookSource::None).build().is_ok());
+    ///  assert!(Solver::builder().shared_table(table).transposition_table_memory(4).build().is_err());
+    ///  assert!(Solver::builder().transposition_table_file("table.bin").transposition_table_size(1024).build().is_err());
+    /// ```
+    pub fn build(self) -> Result<Solver, Box<dyn Error>> {
+        if self.table_file.is_some() && self.transposition_table_size.is_some() {
+            return Err("A transposition table file can't be combined with a table size or memory budget.".into());
+        }
+        if self.shared_table.is_some() && (self.table_file.is_some() || self.transposition_table_size.is_some()) {
+            return Err("A shared transposition table can't be combined with a table file, size or memory budget.".into());
+        }
+
+        let opening_book = match self.book_source {
+            BookSource::None => None,
+            BookSource::Embedded => Some(OpeningBook::from_static_bytes(OPENING_BOOK_BYTES)?),
+            BookSource::File(path) => Some(OpeningBook::load(&path)?),
+            BookSource::Bytes(bytes) => Some(OpeningBook::from_bytes(&bytes)?),
+        };
+
+        let policy = self.replacement_policy;
+        let transposition_table = match (self.table_file, self.transposition_table_size) {
+            (Some(path), _) => TranspositionTable::load(&path)?,
+            (None, Some(TableSize::Entries(entries))) => TranspositionTable::with_size_and_policy(entries, policy),
+            (None, Some(TableSize::Memory(bytes))) => TranspositionTable::with_memory_and_policy(bytes, policy),
+            (None, None) => TranspositionTable::with_size_and_policy(TranspositionTable::MAX_SIZE, policy),
+        };
+        if transposition_table.is_restricted() && self.move_filter.is_none() {
+            return Err("The transposition table file holds results from restricted searches, but no move filter is set.".into());
+        }
+
+        Ok(Solver {
+            explored_positions: 0,
+            threads: self.threads.unwrap_or(1),
+            transposition_table,
+            shared_table: self.shared_table,
+            move_ordering: self.move_ordering.unwrap_or_else(|| Box::new(ThreatOrdering)),
+            enhanced_cutoffs: !self.disable_enhanced_cutoffs,
+            opening_book,
+            statistics: None,
+            observer: self.observer,
+            limits: self.limits,
+            release_table_on_reset: self.release_table_on_reset,
+            parallel_move_scores: self.parallel_move_scores,
+            move_filter: self.move_filter,
+            root_solvers: Vec::new(),
+        })
+    }
+}
\ No newline at end of file
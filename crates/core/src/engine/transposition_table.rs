@@ -1,5 +1,14 @@
 //! A transposition table implementation for storing and retrieving game state evaluations.
 
+use crate::{OpeningBook, Position};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 /// A flag indicating what kind of information a transposition table entry represents.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 #[repr(u8)]
@@ -13,6 +22,18 @@ pub enum TTFlag {
     UpperBound,
 }
 
+impl TTFlag {
+    /// Converts the two low bits of a packed entry back into a flag.
+    #[inline(always)]
+    fn from_bits(bits: u64) -> TTFlag {
+        match bits & 0b11 {
+            1 => TTFlag::LowerBound,
+            2 => TTFlag::UpperBound,
+            _ => TTFlag::Exact,
+        }
+    }
+}
+
 /// Represents a single entry in the transposition table.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct TTEntry {
@@ -24,70 +45,732 @@ pub struct TTEntry {
     pub flag: TTFlag,
     /// The search depth at which this entry was recorded.
     pub depth: u8,
-    /// The age of the transposition table when the entry was created.
-    pub age: u8,
+    /// The best move found in the position, if the search found a move that raised alpha.
+    /// The column is stored in the orientation of the key, which is the mirror image of the
+    /// position if `Position::get_canonical_key` reports that the key is mirrored.
+    pub best_move: Option<usize>,
+    /// The generation of the transposition table when the entry was created.
+    pub age: u16,
+}
+
+impl TTEntry {
+    /// The largest generation which can be stored in an entry.
+    pub(crate) const MAX_AGE: u16 = (1 << 13) - 1;
+
+    /// Returns the exact score of the entry's position, if the entry determines it.
+    ///
+    /// Entries are only stored for positions in which neither player can win with their next
+    /// move, which limits the possible scores, so a bound at either limit is also exact.
+    pub fn exact_score(&self) -> Option<i8> {
+        let depth = self.depth as i8;
+        let (min, max) = (-(depth - 2) / 2, (depth - 1) / 2);
+        match self.flag {
+            TTFlag::Exact => Some(self.value),
+            TTFlag::LowerBound if self.value >= max => Some(max),
+            TTFlag::UpperBound if self.value <= min => Some(min),
+            _ => None,
+        }
+    }
+
+    /// Packs the entry into a single 64-bit word, so that it can be stored atomically.
+    ///
+    /// The depth is at most `Position::BOARD_SIZE`, so only 6 bits are used to store it.
+    /// The best move is stored in 3 bits, with zero representing no move, leaving 13 bits for
+    /// the age.
+    #[inline(always)]
+    pub(crate) fn pack(&self) -> u64 {
+        let best_move = self.best_move.map_or(0, |column| column as u64 + 1);
+        self.key as u64
+            | (self.value as u8 as u64) << 32
+            | (self.flag as u64) << 40
+            | (self.depth as u64 & 0x3F) << 42
+            | (self.age as u64 & Self::MAX_AGE as u64) << 48
+            | best_move << 61
+    }
+
+    /// Unpacks an entry from a 64-bit word created by `pack`.
+    #[inline(always)]
+    pub(crate) fn unpack(data: u64) -> TTEntry {
+        TTEntry {
+            key: data as u32,
+            value: (data >> 32) as u8 as i8,
+            flag: TTFlag::from_bits(data >> 40),
+            depth: (data >> 42) as u8 & 0x3F,
+            best_move: match data >> 61 {
+                0 => None,
+                column => Some(column as usize - 1),
+            },
+            age: (data >> 48) as u16 & Self::MAX_AGE,
+        }
+    }
+}
+
+/// The header written at the start of a saved transposition table file, which is used to
+/// check that the file is compatible before loading its entries.
+#[derive(Debug, Serialize, Deserialize)]
+struct TableHeader {
+    /// A fixed sequence identifying the file as a transposition table.
+    magic: [u8; 4],
+    /// The version of the file format.
+    version: u32,
+    /// The width of the board which the table's positions were searched on.
+    width: u8,
+    /// The height of the board which the table's positions were searched on.
+    height: u8,
+    /// The number of buckets in the table.
+    buckets: u64,
+    /// The table's replacement policy, which determines the size of each bucket.
+    policy: ReplacementPolicy,
+    /// Whether the table holds results from searches restricted by a move filter.
+    restricted: bool,
+}
+
+/// The interface used by the solver's search to store and retrieve entries, which is
+/// implemented by both `TranspositionTable` and `SharedTranspositionTable`.
+pub trait TTStore: Send + Sync {
+    /// Stores a new entry in the table for the position with the given key.
+    fn put(&self, key: u64, value: i8, flag: TTFlag, depth: u8, best_move: Option<usize>);
+
+    /// Retrieves the entry for the position with the given key, if it exists and is valid.
+    fn get(&self, key: u64) -> Option<TTEntry>;
+}
+
+/// A policy deciding which entry of a bucket is overwritten when a new entry is stored.
+///
+/// The table is split into buckets of one or more entries, and a position can be stored in any
+/// entry of the bucket given by its key. The number of entries in each bucket depends on the
+/// policy, and the total memory used by the table is the same for every policy.
+///
+/// # Example
+///
+/// ```rust
+///  use connect_four_ai::{ReplacementPolicy, TranspositionTable, TTFlag, TTStore};
+///
+///  // Finds the entries kept after storing positions with the given depths in one bucket
+///  let kept_entries = |policy: ReplacementPolicy, depths: &[u8]| {
+///      let mut table = TranspositionTable::with_size_and_policy(0, policy);
+///      table.allocate();
+///      let buckets = (table.size() / policy.bucket_size()) as u64;
+///      for (i, &depth) in depths.iter().enumerate() {
+///          table.put(42 + i as u64 * buckets, 0, TTFlag::Exact, depth, None);
+///      }
+///      (0..depths.len()).filter(|&i| table.get(42 + i as u64 * buckets).is_some()).collect::<Vec<_>>()
+///  };
+///
+///  // A single entry is always overwritten, even by a shallower search
+///  assert_eq!(kept_entries(ReplacementPolicy::AlwaysReplace, &[20, 10]), vec![1]);
+///
+///  // The deepest entry is kept, alongside the most recently stored entry
+///  assert_eq!(kept_entries(ReplacementPolicy::TwoTier, &[20, 10, 5]), vec![0, 2]);
+///  assert_eq!(kept_entries(ReplacementPolicy::TwoTier, &[10, 20, 5]), vec![1, 2]);
+///
+///  // Four entries are kept, after which the shallowest entry is overwritten
+///  assert_eq!(kept_entries(ReplacementPolicy::Cluster, &[20, 10, 30, 15]), vec![0, 1, 2, 3]);
+///  assert_eq!(kept_entries(ReplacementPolicy::Cluster, &[20, 10, 30, 15, 12]), vec![0, 2, 3, 4]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReplacementPolicy {
+    /// Buckets contain a single entry, which is always overwritten. This is the default
+    /// policy, as it solves the included test sets fastest overall.
+    #[default]
+    AlwaysReplace,
+    /// Buckets contain two entries: a depth-preferred entry, which is only overwritten by
+    /// entries searched to at least the same depth, and an entry which is always overwritten.
+    TwoTier,
+    /// Buckets contain four entries, packed into half a cache line. An existing entry for the
+    /// same position is overwritten first, followed by entries from previous searches, and
+    /// then the entry with the smallest search depth.
+    Cluster,
+}
+
+impl ReplacementPolicy {
+    /// Returns the number of entries in each bucket of a table using this policy.
+    pub const fn bucket_size(&self) -> usize {
+        match self {
+            ReplacementPolicy::AlwaysReplace => 1,
+            ReplacementPolicy::TwoTier => 2,
+            ReplacementPolicy::Cluster => 4,
+        }
+    }
+}
+
+/// Parses a replacement policy from its name, such as `"two-tier"`.
+impl FromStr for ReplacementPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always-replace" => Ok(ReplacementPolicy::AlwaysReplace),
+            "two-tier" => Ok(ReplacementPolicy::TwoTier),
+            "cluster" => Ok(ReplacementPolicy::Cluster),
+            _ => Err(format!("Unknown replacement policy '{s}'. Expected 'always-replace', 'two-tier' or 'cluster'.")),
+        }
+    }
 }
 
 /// A transposition table that stores results from previous searches to avoid
 /// re-computing evaluations for the same game state.
+///
+/// Each entry is packed into a single atomic word, which makes the table lock-free: it can be
+/// shared between several search threads, each reading and writing entries through a shared
+/// reference. As every entry is written in a single store, a reader can never observe a
+/// partially written entry.
+///
+/// Entries are grouped into buckets, and the table's `ReplacementPolicy` decides which entry
+/// in a bucket is overwritten by each new result.
+///
+/// The entries are allocated lazily: a new table only records its size, and the memory is
+/// allocated by `allocate` before the first search which needs it. This keeps solvers cheap to
+/// create when they only answer positions from an opening book. The memory can be returned to
+/// the allocator with `release`, and is allocated again by the next search.
 #[derive(Debug)]
 pub struct TranspositionTable {
-    /// A list of table entries
-    entries: Vec<TTEntry>,
-    /// The current age of the table, used to invalidate old entries.
-    age: u8,
+    /// A list of packed table entries, which is empty until the table is allocated.
+    entries: Vec<AtomicU64>,
+    /// The number of buckets in the table.
+    buckets: usize,
+    /// The policy used to choose which entry of a bucket to overwrite.
+    policy: ReplacementPolicy,
+    /// The current generation of the table, used to invalidate old entries. This is never
+    /// zero, so that unused entries are never valid.
+    age: u16,
+    /// Whether the table holds results from searches restricted by a move filter.
+    restricted: bool,
 }
 
 impl TranspositionTable {
-    /// The number of entries in the table. A large prime number is chosen to help avoid collisions.
+    /// The default number of entries in the table. A large prime number is chosen to help avoid
+    /// collisions.
     pub const MAX_SIZE: usize = (1 << 23) + 9;
 
-    /// Creates a new empty transposition table, allocating space for all entries.
+    /// The minimum number of buckets in the table.
+    ///
+    /// Entries only store the lower 32 bits of each 49-bit position key, with the rest of the
+    /// key implied by the entry's bucket. By the Chinese Remainder Theorem, this uniquely
+    /// identifies each position as long as the number of buckets is odd and at least 2^17.
+    /// The smallest prime number above 2^17 is chosen.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{ReplacementPolicy, TranspositionTable};
+    ///
+    ///  // Keys sharing their lower 32 bits differ by a multiple of 2^32, so two such keys in the
+    ///  // same bucket differ by a multiple of 2^32 * buckets, which exceeds every 49-bit key
+    ///  assert_eq!(TranspositionTable::MIN_SIZE % 2, 1);
+    ///  assert!((1u64 << 32) * TranspositionTable::MIN_SIZE as u64 >= 1 << 49);
+    ///
+    ///  // Every table size satisfies the condition, however the table is created
+    ///  for policy in [ReplacementPolicy::AlwaysReplace, ReplacementPolicy::TwoTier, ReplacementPolicy::Cluster] {
+    ///      for amount in [0, 1, 1 << 17, 1 << 20, 3 << 20, TranspositionTable::MAX_SIZE] {
+    ///          for table in [
+    ///              TranspositionTable::with_size_and_policy(amount, policy),
+    ///              TranspositionTable::with_memory_and_policy(amount, policy),
+    ///          ] {
+    ///              let buckets = table.size() / policy.bucket_size();
+    ///              assert_eq!(buckets % 2, 1);
+    ///              assert!(buckets >= TranspositionTable::MIN_SIZE);
+    ///          }
+    ///      }
+    ///  }
+    /// ```
+    pub const MIN_SIZE: usize = (1 << 17) + 29;
+
+    /// Creates a new empty transposition table with the default size.
     pub fn new() -> TranspositionTable {
         Self::default()
     }
 
-    /// Calculates the table index for a given position's key.
+    /// Creates a new empty transposition table with at least the given number of entries,
+    /// using the default replacement policy.
+    ///
+    /// The number of buckets is rounded up to the nearest prime number, which helps to avoid
+    /// collisions and guarantees correctness, and is never less than `MIN_SIZE`.
+    pub fn with_size(entries: usize) -> TranspositionTable {
+        Self::with_size_and_policy(entries, ReplacementPolicy::default())
+    }
+
+    /// Creates a new empty transposition table with at least the given number of entries,
+    /// using the given replacement policy.
+    pub fn with_size_and_policy(entries: usize, policy: ReplacementPolicy) -> TranspositionTable {
+        let buckets = entries.div_ceil(policy.bucket_size());
+        Self::unallocated(Self::next_prime(buckets.max(Self::MIN_SIZE)), policy)
+    }
+
+    /// Creates a new empty transposition table which fits within the given memory budget,
+    /// using the default replacement policy.
+    ///
+    /// The number of buckets is the largest prime number which fits within the budget, but is
+    /// never less than `MIN_SIZE`, so very small budgets may be exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: The maximum amount of memory to allocate for the table's entries, in bytes.
+    pub fn with_memory(bytes: usize) -> TranspositionTable {
+        Self::with_memory_and_policy(bytes, ReplacementPolicy::default())
+    }
+
+    /// Creates a new empty transposition table which fits within the given memory budget,
+    /// using the given replacement policy.
+    pub fn with_memory_and_policy(bytes: usize, policy: ReplacementPolicy) -> TranspositionTable {
+        let buckets = bytes / (policy.bucket_size() * size_of::<AtomicU64>());
+        if buckets <= Self::MIN_SIZE {
+            Self::unallocated(Self::MIN_SIZE, policy)
+        } else {
+            Self::unallocated(Self::previous_prime(buckets), policy)
+        }
+    }
+
+    /// Creates an unallocated table with exactly the given number of buckets.
+    fn unallocated(buckets: usize, policy: ReplacementPolicy) -> TranspositionTable {
+        TranspositionTable {
+            entries: Vec::new(),
+            buckets,
+            policy,
+            age: 1,
+            restricted: false,
+        }
+    }
+
+    /// Allocates space for all entries in the table, if it is not already allocated.
+    ///
+    /// This must be called before any entries are stored or retrieved.
+    pub fn allocate(&mut self) {
+        if self.entries.is_empty() {
+            self.entries = (0..self.size()).map(|_| AtomicU64::new(0)).collect();
+        }
+    }
+
+    /// Releases the memory used by the table's entries back to the allocator, which also
+    /// clears all entries. The memory is allocated again by the next call to `allocate`.
+    pub fn release(&mut self) {
+        self.entries = Vec::new();
+        self.restricted = false;
+    }
+
+    /// Records that the table holds results from searches restricted by a move filter, which
+    /// aren't valid without the filter. The record is cleared by `reset` and `release`.
+    pub(crate) fn mark_restricted(&mut self) {
+        self.restricted = true;
+    }
+
+    /// Indicates whether the table holds results from searches restricted by a move filter.
+    pub fn is_restricted(&self) -> bool {
+        self.restricted
+    }
+
+    /// The sequence of bytes identifying a saved transposition table file.
+    const FILE_MAGIC: [u8; 4] = *b"C4TT";
+
+    /// The current version of the saved transposition table file format.
+    pub const FILE_VERSION: u32 = 3;
+
+    /// Saves the table's valid entries to a file, so that a later search can be warm-started.
+    ///
+    /// The file starts with a header recording the board size, the table size, the format
+    /// version and whether the table holds results from restricted searches, followed by every
+    /// entry of the table. Entries from previous searches are
+    /// cleared in the file, and an unallocated table is saved as an empty table.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, Position, Solver};
+    ///
+    ///  let pos = Position::from_moves("52753311433677442422121").unwrap();
+    ///  let path = std::env::temp_dir().join("connect-four-ai-table.bin");
+    ///
+    ///  let mut solver = Solver::builder()
+    ///      .transposition_table_memory(4)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///  assert_eq!(solver.solve(&pos), 8);
+    ///  solver.transposition_table.save(&path).unwrap();
+    ///
+    ///  // A solver warm-started from the saved table finds the score with fewer nodes
+    ///  let mut warm_solver = Solver::builder()
+    ///      .transposition_table_file(&path)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///  assert_eq!(warm_solver.solve(&pos), 8);
+    ///  assert!(warm_solver.explored_positions < solver.explored_positions);
+    ///  # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let file = File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        let header = TableHeader {
+            magic: Self::FILE_MAGIC,
+            version: Self::FILE_VERSION,
+            width: Position::WIDTH as u8,
+            height: Position::HEIGHT as u8,
+            buckets: self.buckets as u64,
+            policy: self.policy,
+            restricted: self.restricted,
+        };
+        bincode::serialize_into(&mut writer, &header)?;
+
+        for index in 0..self.size() {
+            let data = match self.entries.get(index) {
+                Some(data) => data.load(Ordering::Relaxed),
+                None => 0,
+            };
+
+            // Valid entries are saved in the first generation, and all others are cleared
+            let mut entry = TTEntry::unpack(data);
+            let data = if entry.age == self.age {
+                entry.age = 1;
+                entry.pack()
+            } else {
+                0
+            };
+            writer.write_all(&data.to_le_bytes())?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a table from a file created by `save`.
+    ///
+    /// The loaded table has the same size and replacement policy as the saved table, contains
+    /// all of its valid entries, and is restricted if the saved table was.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or if its header doesn't match the current
+    /// format version and board size or gives an invalid table size.
+    pub fn load(path: &Path) -> Result<TranspositionTable, Box<dyn Error>> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let header: TableHeader = bincode::deserialize_from(&mut reader)?;
+
+        if header.magic != Self::FILE_MAGIC {
+            return Err("The file is not a saved transposition table.".into());
+        }
+        if header.version != Self::FILE_VERSION {
+            return Err(format!(
+                "Unsupported transposition table format version {} (expected {}).",
+                header.version, Self::FILE_VERSION,
+            ).into());
+        }
+        if (header.width as usize, header.height as usize) != (Position::WIDTH, Position::HEIGHT) {
+            return Err(format!(
+                "The transposition table was saved for a {}x{} board, but the board is {}x{}.",
+                header.width, header.height, Position::WIDTH, Position::HEIGHT,
+            ).into());
+        }
+        let buckets = usize::try_from(header.buckets)?;
+        if buckets < Self::MIN_SIZE || buckets.is_multiple_of(2) {
+            return Err(format!("Invalid number of transposition table buckets: {buckets}.").into());
+        }
+        let size = buckets.checked_mul(header.policy.bucket_size())
+            .ok_or_else(|| format!("Invalid number of transposition table buckets: {buckets}."))?;
+
+        let mut table = Self::unallocated(buckets, header.policy);
+        table.restricted = header.restricted;
+        let mut bytes = [0; size_of::<u64>()];
+        table.entries = (0..size)
+            .map(|_| {
+                reader.read_exact(&mut bytes)?;
+                Ok(AtomicU64::new(u64::from_le_bytes(bytes)))
+            })
+            .collect::<Result<_, std::io::Error>>()?;
+        Ok(table)
+    }
+
+    /// Creates an opening book containing the exact scores of every position in the table.
+    ///
+    /// As well as entries with the `Exact` flag, this includes bounds which pin a position's
+    /// score to the highest or lowest score possible with its number of remaining moves, as
+    /// the null-window searches used by the solver only store bounds.
+    ///
+    /// The table only stores the lower 32 bits of each key, but as with look-ups, the full
+    /// key is recovered from the entry's bucket using the Chinese Remainder Theorem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table holds results from searches restricted by a move filter,
+    /// as their scores aren't valid without the filter. This is recorded when a table is saved,
+    /// so it also applies to tables loaded with `load`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{Position, TranspositionTable, TTFlag, TTStore};
+    ///
+    ///  let mut table = TranspositionTable::with_size(0);
+    ///  table.allocate();
+    ///
+    ///  let exact = Position::from_moves("4453").unwrap();
+    ///  let bound = Position::from_moves("4455").unwrap();
+    ///  table.put(exact.get_key(), 3, TTFlag::Exact, 38, Some(3));
+    ///  table.put(bound.get_key(), 2, TTFlag::LowerBound, 38, Some(3));
+    ///
+    ///  // Only exact scores are promoted into the book
+    ///  let book = table.to_opening_book().unwrap();
+    ///  assert_eq!(book.get(&exact), Some(3));
+    ///  assert_eq!(book.get(&bound), None);
+    /// ```
+    ///
+    /// Every score in a book created from a solver's table matches the solver's exact score:
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{BookSource, ForbiddenColumns, Position, Solver, TranspositionTable};
+    ///
+    ///  let new_solver = || Solver::builder()
+    ///      .transposition_table_memory(4)
+    ///      .opening_book(BookSource::None)
+    ///      .build()
+    ///      .unwrap();
+    ///  let (mut solver, mut reference) = (new_solver(), new_solver());
+    ///  let pos = Position::from_moves("274552224131661").unwrap();
+    ///  assert_eq!(solver.solve(&pos), 0);
+    ///  let book = solver.transposition_table.to_opening_book().unwrap();
+    ///
+    ///  // Checks the book's scores for every position up to four moves after the solved one
+    ///  let mut positions = vec![pos];
+    ///  let mut checked = 0;
+    ///  for _ in 0..4 {
+    ///      positions = positions.iter().flat_map(|position| {
+    ///          (0..Position::WIDTH).filter(|&col| position.is_playable(col)).map(|col| {
+    ///              let mut child = *position;
+    ///              child.play(col);
+    ///              child
+    ///          })
+    ///      }).collect();
+    ///      for position in &positions {
+    ///          if let Some(score) = book.get(position) {
+    ///              reference.reset();
+    ///              assert_eq!(score, reference.solve(position));
+    ///              checked += 1;
+    ///          }
+    ///      }
+    ///  }
+    ///  assert!(checked > 10);
+    ///
+    ///  // Tables filled by restricted searches can't be converted into a book, even once saved
+    ///  solver.set_move_filter(ForbiddenColumns::new(&[3]));
+    ///  solver.solve(&pos);
+    ///  assert!(solver.transposition_table.to_opening_book().is_err());
+    ///
+    ///  let path = std::env::temp_dir().join("connect-four-ai-restricted-table.bin");
+    ///  solver.transposition_table.save(&path).unwrap();
+    ///  let table = TranspositionTable::load(&path).unwrap();
+    ///  assert!(table.is_restricted());
+    ///  assert!(table.to_opening_book().is_err());
+    ///  assert!(Solver::builder().transposition_table_file(&path).build().is_err());
+    ///  # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn to_opening_book(&self) -> Result<OpeningBook, Box<dyn Error>> {
+        if self.restricted {
+            return Err("The table holds results from searches restricted by a move filter.".into());
+        }
+
+        let mut book = OpeningBook::new();
+        let bucket_size = self.policy.bucket_size();
+        let modulus = self.buckets as i128;
+
+        // The inverse of 2^32 modulo the number of buckets, which is used to recover keys
+        let inverse = Self::modular_inverse(1 << 32, modulus);
+
+        for (index, data) in self.entries.iter().enumerate() {
+            let entry = TTEntry::unpack(data.load(Ordering::Relaxed));
+            let score = match entry.exact_score() {
+                Some(score) if entry.age == self.age => score,
+                _ => continue,
+            };
+
+            // Finds the key which is congruent to the entry's bucket and partial key
+            let bucket = (index / bucket_size) as i128;
+            let partial_key = entry.key as i128;
+            let high_bits = ((bucket - partial_key) * inverse).rem_euclid(modulus);
+            let key = (partial_key + (high_bits << 32)) as u64;
+            book.map.insert(key, score);
+        }
+
+        Ok(book)
+    }
+
+    /// Calculates the inverse of `value` modulo `modulus` using the extended Euclidean
+    /// algorithm, assuming that they are coprime.
+    fn modular_inverse(value: i128, modulus: i128) -> i128 {
+        let (mut old_r, mut r) = (value.rem_euclid(modulus), modulus);
+        let (mut old_s, mut s) = (1, 0);
+        while r != 0 {
+            let quotient = old_r / r;
+            (old_r, r) = (r, old_r - quotient * r);
+            (old_s, s) = (s, old_s - quotient * s);
+        }
+        old_s.rem_euclid(modulus)
+    }
+
+    /// Indicates whether space for the table's entries is currently allocated.
+    pub fn is_allocated(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// Returns the number of entries in the table.
+    pub fn size(&self) -> usize {
+        self.buckets * self.policy.bucket_size()
+    }
+
+    /// Returns the amount of memory used by the table's entries once allocated, in bytes.
+    pub fn memory(&self) -> usize {
+        self.size() * size_of::<AtomicU64>()
+    }
+
+    /// Returns the table's replacement policy.
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+
+    /// Returns the smallest prime number greater than or equal to `n`.
+    pub(crate) fn next_prime(mut n: usize) -> usize {
+        while !Self::is_prime(n) {
+            n += 1;
+        }
+        n
+    }
+
+    /// Returns the largest prime number less than or equal to `n`, where `n` is at least 2.
+    pub(crate) fn previous_prime(mut n: usize) -> usize {
+        while !Self::is_prime(n) {
+            n -= 1;
+        }
+        n
+    }
+
+    /// Indicates whether `n` is a prime number, using trial division.
+    fn is_prime(n: usize) -> bool {
+        if n < 4 {
+            return n >= 2;
+        }
+        if n.is_multiple_of(2) {
+            return false;
+        }
+        (3..).step_by(2).take_while(|i| i * i <= n).all(|i| !n.is_multiple_of(i))
+    }
+
+    /// Calculates the index of the bucket for a given position's key.
     #[inline(always)]
     pub fn index(&self, key: u64) -> usize {
-        (key % Self::MAX_SIZE as u64) as usize
+        (key % self.buckets as u64) as usize
     }
 
-    /// Clears the table by incrementing the current age.
+    /// Returns the entries of the bucket for a given position's key.
+    #[inline(always)]
+    fn bucket(&self, key: u64) -> &[AtomicU64] {
+        let size = self.policy.bucket_size();
+        let start = self.index(key) * size;
+        &self.entries[start..start + size]
+    }
+
+    /// Clears the table by incrementing the current generation.
+    ///
+    /// Entries are only valid if they were stored in the current generation. When the
+    /// generation counter runs out, every entry is cleared and the counter restarts, so stale
+    /// entries can never become valid again, no matter how many times the table is reset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use connect_four_ai::{TranspositionTable, TTFlag, TTStore};
+    ///
+    ///  let mut table = TranspositionTable::with_size(0);
+    ///  table.allocate();
+    ///  table.put(42, 5, TTFlag::Exact, 10, None);
+    ///  assert!(table.get(42).is_some());
+    ///
+    ///  // The entry stays invalid across any number of resets, including those which wrap
+    ///  // around the 13-bit generation counter, while new entries are valid in every generation
+    ///  for resets in 1..=(1 << 17) {
+    ///      table.reset();
+    ///      assert!(table.get(42).is_none(), "stale entry found after {resets} resets");
+    ///      table.put(43, 5, TTFlag::Exact, 10, None);
+    ///      assert!(table.get(43).is_some(), "new entry lost after {resets} resets");
+    ///  }
+    /// ```
     pub fn reset(&mut self) {
-        self.age = self.age.wrapping_add(1);
+        self.restricted = false;
+        if self.age == TTEntry::MAX_AGE {
+            for entry in &self.entries {
+                entry.store(0, Ordering::Relaxed);
+            }
+            self.age = 1;
+        } else {
+            self.age += 1;
+        }
     }
 
-    /// Stores a new entry in the table, overwriting any existing entry at the calculated index.
-    pub fn put(&mut self, key: u64, value: i8, flag: TTFlag, depth: u8) {
-        let pos = self.index(key);
-        self.entries[pos].key = key as u32;
-        self.entries[pos].value = value;
-        self.entries[pos].flag = flag;
-        self.entries[pos].depth = depth;
-        self.entries[pos].age = self.age;
+    /// Chooses which entry of a cluster to overwrite with a new entry.
+    #[inline(always)]
+    fn cluster_slot(&self, bucket: &[AtomicU64], entry: &TTEntry) -> usize {
+        let mut slot = 0;
+        let mut lowest_depth = u8::MAX;
+        for (i, data) in bucket.iter().enumerate() {
+            let existing = TTEntry::unpack(data.load(Ordering::Relaxed));
+            if existing.age != self.age {
+                // Entries from previous searches are replaced before any current entries
+                if lowest_depth > 0 {
+                    slot = i;
+                    lowest_depth = 0;
+                }
+            } else if existing.key == entry.key {
+                return i;
+            } else if existing.depth < lowest_depth {
+                slot = i;
+                lowest_depth = existing.depth;
+            }
+        }
+        slot
     }
+}
 
-    /// Retrieves an entry from the table if it exists and is valid.
-    pub fn get(&self, key: u64) -> Option<&TTEntry> {
-        let pos = self.index(key);
-        let entry = &self.entries[pos];
+impl TTStore for TranspositionTable {
+    /// Stores a new entry in the table, overwriting an entry in its bucket chosen by the
+    /// table's replacement policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table has not been allocated.
+    fn put(&self, key: u64, value: i8, flag: TTFlag, depth: u8, best_move: Option<usize>) {
+        let entry = TTEntry { key: key as u32, value, flag, depth, best_move, age: self.age };
+        let bucket = self.bucket(key);
+        let slot = match self.policy {
+            ReplacementPolicy::AlwaysReplace => 0,
+            ReplacementPolicy::TwoTier => {
+                let existing = TTEntry::unpack(bucket[0].load(Ordering::Relaxed));
+                if existing.age != self.age || existing.key == entry.key || depth >= existing.depth {
+                    0
+                } else {
+                    1
+                }
+            }
+            ReplacementPolicy::Cluster => self.cluster_slot(bucket, &entry),
+        };
+        bucket[slot].store(entry.pack(), Ordering::Relaxed);
+    }
 
+    /// Retrieves an entry from the table if it exists and is valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table has not been allocated.
+    fn get(&self, key: u64) -> Option<TTEntry> {
         // Checks that both the key and age match to ensure correctness
-        if entry.key == key as u32 && entry.age == self.age {
-            Some(entry)
-        } else {
-            None
-        }
+        self.bucket(key)
+            .iter()
+            .map(|data| TTEntry::unpack(data.load(Ordering::Relaxed)))
+            .find(|entry| entry.key == key as u32 && entry.age == self.age)
     }
 }
 
 /// Default constructor for the `TranspositionTable` struct.
 impl Default for TranspositionTable {
     fn default() -> TranspositionTable {
-        TranspositionTable {
-            entries: vec![TTEntry::default(); Self::MAX_SIZE],
-            age: 0,
-        }
+        Self::with_size(Self::MAX_SIZE)
     }
 }
\ No newline at end of file
@@ -11,11 +11,51 @@ pub use engine::{
     TTFlag,
     TTEntry,
     TranspositionTable,
+    ReplacementPolicy,
+    TTStore,
+    SharedTranspositionTable,
     MoveEntry,
     MoveSorter,
+    MoveOrdering,
+    ThreatOrdering,
+    HistoryOrdering,
+    KillerOrdering,
+    TTMoveFirst,
     OpeningBook,
     OpeningBookGenerator,
     Difficulty,
-    AIPlayer
+    AIPlayer,
+    SearchStatistics,
+    SearchObserver,
+    SearchProgress,
+    SearchLimits,
+    SolverBuilder,
+    BookSource,
+    OutcomeProver,
+    ProofNumberSolver,
+    Engine,
+    HeuristicEngine,
+    FallbackEngine,
+    MctsEngine,
+    PlayoutPolicy,
+    RuleEvaluator,
+    Rule,
+    RuleApplication,
+    RuleSet,
+    Square,
+    MoveAnalysis,
+    Outcome,
+    GameAnalyser,
+    GameReport,
+    MoveAnnotation,
+    MoveClassification,
+    PuzzleGenerator,
+    PuzzleFilter,
+    PuzzleKind,
+    Puzzle,
+    MoveFilter,
+    ForbiddenColumns,
+    Handicap,
+    HandicapError,
 };
-pub use board::{Position, PositionParsingError};
+pub use board::{Position, PositionParsingError};
\ No newline at end of file
@@ -5,6 +5,8 @@ This library provides functionality to compute the score and optimal move for an
 given Connect Four position.
 """
 
+from typing import Any, Callable, Protocol
+
 class Position:
     """Represents a Connect Four position compactly as a bitboard.
 
@@ -146,8 +148,24 @@ class Solver:
     explored_positions: int
     """A counter for the number of nodes explored in the last `solve` call."""
 
-    def __init__(self) -> None:
-        """Creates a new `Solver` instance, using the pre-packaged opening book."""
+    transposition_table_size: int
+    """The number of entries in the solver's transposition table."""
+
+    release_table_on_reset: bool
+    """
+    Whether `reset` releases the memory used by the solver's transposition table.
+
+    The table is allocated by the first search which needs it, so releasing it stops idle
+    solvers from holding on to its memory.
+    """
+
+    def __init__(self, transposition_table_memory: int | None = None) -> None:
+        """
+        Creates a new `Solver` instance, using the pre-packaged opening book.
+
+        If `transposition_table_memory` is given, the solver's transposition table is sized to
+        fit within that many megabytes, instead of the default of around 64 MB.
+        """
 
     def load_opening_book(self, path: str) -> bool:
         """
@@ -156,6 +174,18 @@ class Solver:
         Returns whether the opening book was successfully loaded.
         """
 
+    def set_progress_callback(
+        self, callback: Callable[[dict[str, Any]], None], node_interval: int = 1 << 20
+    ) -> None:
+        """
+        Sets a callback which receives progress reports during each search.
+
+        The callback is called with a dictionary describing the search's progress at each
+        iteration of the binary search, and after every `node_interval` explored nodes.
+        The dictionary contains the `iteration`, `min`, `max` and `mid` values of the current
+        search window, the number of `explored_positions`, and the `elapsed` time in seconds.
+        """
+
     def reset(self) -> None:
         """Resets the solver's state."""
 
@@ -185,6 +215,20 @@ class Solver:
             move, -2 if the opponent wins with their second to last move, ...
         """
 
+    def solve_with_hint(self, position: Position, expected_score: int) -> int:
+        """Solves a position to find its exact score, starting from a narrow window around
+        an expected score.
+
+        The exact score is always found, but it is found fastest when the hint is correct.
+
+        Parameters
+        ----------
+        position : Position
+            The board position to solve.
+        expected_score : int
+            The expected score of the position.
+        """
+
     def get_all_move_scores(self, position: Position) -> list[int | None]:
         """
         Calculates the scores for all possible next moves in the given position.
@@ -196,6 +240,43 @@ class Solver:
         This array can be used to directly calculate the optimal move to play in a position.
         """
 
+    name: str
+    """The name of the solver as an engine, allowing solvers to be used as AI player engines."""
+
+    def evaluate(self, position: Position) -> int:
+        """Evaluates a position to find its exact score, which is equivalent to `solve`."""
+
+    def move_scores(self, position: Position) -> list[int | None]:
+        """
+        Calculates the scores for all possible next moves in the given position, which is
+        equivalent to `get_all_move_scores`.
+        """
+
+    def best_move(self, position: Position) -> int | None:
+        """
+        Returns the optimal move in the given position, or `None` if no moves are possible.
+
+        Ties are broken in favour of columns closer to the centre.
+        """
+
+class Engine(Protocol):
+    """
+    An engine which evaluates positions and scores their possible moves, which can be used
+    by an `AIPlayer` instead of its default solver, such as a remote engine or a test double.
+
+    Scores use the same scale as the `Solver`'s exact scores. The `reset` method and `name`
+    attribute are optional. A `Solver` instance can itself be used as an engine.
+    """
+
+    def evaluate(self, position: Position) -> int:
+        """Evaluates a position, returning its score from the current player's perspective."""
+
+    def move_scores(self, position: Position) -> list[int | None]:
+        """
+        Calculates the scores for all possible next moves in the given position, with `None`
+        for each full column.
+        """
+
 class Difficulty:
     """An enum to represent the difficulty of an AI player."""
 
@@ -204,16 +285,106 @@ class Difficulty:
     HARD: Difficulty
     IMPOSSIBLE: Difficulty
 
+class Handicap:
+    """
+    A handicap for a game between a human and an AI player.
+
+    Each handicap decides the game's starting position, which player moves first, and any
+    restriction on the AI's opening move. A game without a handicap starts from the empty
+    board with the AI moving first.
+    """
+
+    NONE: Handicap
+    """The standard game, in which the AI moves first."""
+
+    AI_MOVES_SECOND: Handicap
+    """The standard game, in which the human moves first."""
+
+    EDGE_OPENING: Handicap
+    """The AI moves first, but must open in one of the edge columns."""
+
+    human_moves_first: bool
+    """Whether the human player makes the first move of the game."""
+
+    @staticmethod
+    def human_opening(column: int) -> Handicap:
+        """
+        The human moves first, with their opening disc played in the given 0-based column,
+        after which the AI moves.
+
+        Raises a `ValueError` if the column is out of range.
+        """
+
+    @staticmethod
+    def all() -> list[Handicap]:
+        """Returns the catalogue of handicaps, from no handicap to the strongest."""
+
+    def __str__(self) -> str:
+        """Returns a description of the handicap."""
+
+    def position(self) -> Position:
+        """
+        Returns the starting position of a game with the handicap, whose current player is
+        the human if `human_moves_first` is true, and the AI otherwise.
+        """
+
+    def outcome(self, solver: Solver) -> str:
+        """
+        Finds the theoretical outcome of a game with the handicap from the human's
+        perspective, which is one of 'win', 'draw' or 'loss'.
+        """
+
 class AIPlayer:
     """
-    An AI player that uses a solver to determine the best move to play in a Connect Four position.
+    An AI player that uses an engine to determine the best move to play in a Connect Four
+    position. By default, the engine is a perfect solver.
 
     The player's skill level can be configured using the `Difficulty` enum, which adjusts the
     move selection strategy.
     """
 
-    def __init__(self, difficulty: Difficulty = Difficulty.IMPOSSIBLE) -> None:
-        """Creates a new AI player with a default solver and specified difficulty."""
+    engine_name: str
+    """The name of the AI player's engine."""
+
+    transposition_table_size: int
+    """The number of entries in the AI player's transposition table."""
+
+    release_table_on_reset: bool
+    """
+    Whether `reset` releases the memory used by the AI player's transposition table.
+
+    The table is allocated by the first search which needs it, so releasing it stops idle
+    players from holding on to its memory.
+    """
+
+    handicap: Handicap
+    """
+    The handicap applied to the AI player's moves, which stops it from scoring or selecting
+    any moves the handicap doesn't allow.
+    """
+
+    def __init__(
+        self,
+        difficulty: Difficulty = Difficulty.IMPOSSIBLE,
+        parallel: bool = False,
+        transposition_table_memory: int | None = None,
+        engine: Engine | None = None,
+    ) -> None:
+        """
+        Creates a new AI player with the specified difficulty.
+
+        If `engine` is given, the player uses it to score its moves instead of the default
+        solver. It can be any object with `evaluate(position)` and `move_scores(position)`
+        methods, and optionally a `reset()` method and a `name` attribute.
+
+        The remaining settings only apply to the default solver. If `parallel` is true, the
+        possible moves in each position are evaluated concurrently. If
+        `transposition_table_memory` is given, the solver's transposition table is sized to
+        fit within that many megabytes, instead of the default of around 64 MB.
+        """
+
+    def set_parallel(self, parallel: bool) -> None:
+        """Sets whether the AI player evaluates the possible moves in each position concurrently."""
 
     def load_opening_book(self, path: str) -> bool:
         """
@@ -222,16 +393,31 @@ class AIPlayer:
         Returns whether the opening book was successfully loaded.
         """
 
+    def set_progress_callback(
+        self, callback: Callable[[dict[str, Any]], None], node_interval: int = 1 << 20
+    ) -> None:
+        """
+        Sets a callback which receives progress reports while the AI player searches for a move.
+
+        The callback is called with a dictionary describing the search's progress at each
+        iteration of the binary search, and after every `node_interval` explored nodes.
+        The dictionary contains the `iteration`, `min`, `max` and `mid` values of the current
+        search window, the number of `explored_positions`, and the `elapsed` time in seconds.
+        """
+
     def reset(self) -> None:
-        """Resets the AI player's solver."""
+        """Resets the AI player's engine."""
     
     def solve(self, position: Position) -> int:
-        """Solves a position to find its exact score using the AI player's solver."""
+        """
+        Evaluates a position to find its score using the AI player's engine. With the default
+        solver, this is the position's exact score.
+        """
 
     def get_all_move_scores(self, position: Position) -> list[int | None]:
         """
         Calculates the scores for all possible next moves in the given position
-        using the AI player's solver.
+        using the AI player's engine.
         """
 
     def get_move(self, position: Position) -> int | None:
@@ -245,4 +431,45 @@ class AIPlayer:
 
         Returns the column index of the selected move or `None` if no moves are possible.
         """
-        
+
+class GameAnalyser:
+    """
+    An analyser which replays a game, solving every possible move at each ply to annotate
+    the quality of the moves which were played.
+
+    Each move is classified as best, good, an inaccuracy, a mistake or a blunder, by comparing
+    its outcome and score with the best move available. The analyser's solver keeps its
+    transposition table between plies and games.
+    """
+
+    good_score_loss: int
+    """
+    The largest score loss for which a move keeping the best outcome is classified as good
+    rather than an inaccuracy.
+    """
+
+    def __init__(self, parallel: bool = False, transposition_table_memory: int | None = None) -> None:
+        """
+        Creates a new game analyser.
+
+        If `parallel` is true, the possible moves at each ply are evaluated concurrently. If
+        `transposition_table_memory` is given, the solver's transposition table is sized to
+        fit within that many megabytes, instead of the default of around 64 MB.
+        """
+
+    def reset(self) -> None:
+        """Resets the analyser's solver."""
+
+    def analyse(self, moves: str) -> dict[str, Any]:
+        """
+        Analyses a game given as a string of 1-indexed moves, returning a report annotating
+        each of its moves.
+
+        The report is a dictionary containing the game's `moves`, a list of `annotations`,
+        the `accuracy` of each player as a list of two percentages, and the `winner` (1, 2 or
+        `None`). Each annotation is a dictionary containing the move's `ply`, `player`,
+        `column`, `score`, `best_score`, `best_moves`, `accuracy` and `classification`.
+
+        Raises a `ValueError` if the moves are invalid, including any move made after the
+        game has been won.
+        """
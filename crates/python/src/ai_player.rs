@@ -1,4 +1,4 @@
-//! Provides the logic for an AI player which utilises a solver to select which move to play
+//! Provides the logic for an AI player which utilises an engine to select which move to play
 //! in a given Connect Four position.
 //!
 //! The AI's skill is determined by the `Difficulty` enum, which controls the temperature of
@@ -6,9 +6,14 @@
 //! of behaviours, from a more random 'Easy' player to a perfect, greedy 'Impossible' player.
 
 use std::path::Path;
-use connect_four_ai::{AIPlayer, Difficulty, Position};
+use connect_four_ai::{AIPlayer, Difficulty, Engine, Position};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use crate::engine::{PlayerEngine, PyObjectEngine};
+use crate::handicap::PyHandicap;
 use crate::position::PyPosition;
+use crate::search_observer::PyCallbackObserver;
+use crate::solver::build_solver;
 
 /// An enum to represent the difficulty of an AI player.
 #[pyclass(name="Difficulty")]
@@ -27,42 +32,125 @@ impl PyDifficulty {
     const IMPOSSIBLE: Self = Self(Difficulty::Impossible);
 }
 
-/// An AI player that uses a solver to determine the best move to play in a Connect Four position.
+/// An AI player that uses an engine to determine the best move to play in a Connect Four
+/// position. By default, the engine is a perfect solver.
 ///
 /// The player's skill level can be configured using the `Difficulty` enum, which adjusts the
 /// move selection strategy.
 #[pyclass(name="AIPlayer")]
 #[derive(Debug)]
-pub struct PyAIPlayer(AIPlayer);
+pub struct PyAIPlayer(AIPlayer<PlayerEngine>);
 
 #[pymethods]
 impl PyAIPlayer {
-    /// Creates a new AI player with a default solver and specified difficulty.
+    /// Creates a new AI player with the specified difficulty.
+    ///
+    /// If `engine` is given, the player uses it to score its moves instead of the default
+    /// solver. It can be any object with `evaluate(position)` and `move_scores(position)`
+    /// methods, and optionally a `reset()` method and a `name` attribute.
+    ///
+    /// The remaining settings only apply to the default solver. If `parallel` is true, the
+    /// possible moves in each position are evaluated concurrently. If
+    /// `transposition_table_memory` is given, the solver's transposition table is sized to
+    /// fit within that many megabytes, instead of the default of around 64 MB.
     #[new]
-    #[pyo3(signature=(difficulty=PyDifficulty::IMPOSSIBLE))]
-    fn new(difficulty: PyDifficulty) -> PyAIPlayer {
-        PyAIPlayer(AIPlayer::new(difficulty.0))
+    #[pyo3(signature=(difficulty=PyDifficulty::IMPOSSIBLE, parallel=false, transposition_table_memory=None, engine=None))]
+    fn new(
+        py: Python,
+        difficulty: PyDifficulty,
+        parallel: bool,
+        transposition_table_memory: Option<usize>,
+        engine: Option<Py<PyAny>>,
+    ) -> PyResult<PyAIPlayer> {
+        let engine = match engine {
+            Some(_) if parallel || transposition_table_memory.is_some() => {
+                return Err(PyValueError::new_err("Solver settings can't be used with a custom engine."));
+            }
+            Some(object) => PlayerEngine::Object(PyObjectEngine::new(py, object)?),
+            None => {
+                let mut solver = build_solver(transposition_table_memory)?;
+                solver.parallel_move_scores = parallel;
+                PlayerEngine::Solver(Box::new(solver))
+            }
+        };
+        Ok(PyAIPlayer(AIPlayer::with_engine(difficulty.0, engine)))
+    }
+
+    /// The name of the AI player's engine.
+    #[getter]
+    fn get_engine_name(&self) -> String {
+        self.0.engine().name().to_string()
+    }
+
+    /// The number of entries in the AI player's transposition table.
+    #[getter]
+    fn get_transposition_table_size(&self) -> PyResult<usize> {
+        Ok(self.0.engine().solver()?.transposition_table.size())
+    }
+
+    /// Whether `reset` releases the memory used by the AI player's transposition table.
+    ///
+    /// The table is allocated by the first search which needs it, so releasing it stops idle
+    /// players from holding on to its memory.
+    #[getter]
+    fn get_release_table_on_reset(&self) -> PyResult<bool> {
+        Ok(self.0.engine().solver()?.release_table_on_reset)
+    }
+
+    #[setter]
+    fn set_release_table_on_reset(&mut self, release: bool) -> PyResult<()> {
+        self.0.engine_mut().solver_mut()?.release_table_on_reset = release;
+        Ok(())
+    }
+
+    /// Sets whether the AI player evaluates the possible moves in each position concurrently.
+    fn set_parallel(&mut self, parallel: bool) -> PyResult<()> {
+        self.0.engine_mut().solver_mut()?.parallel_move_scores = parallel;
+        Ok(())
     }
 
     /// Attempts to load an opening book from the given path for the AI player's solver.
     ///
     /// Returns whether the opening book was successfully loaded.
-    fn load_opening_book(&mut self, path: &str) -> bool {
-        self.0.load_opening_book(Path::new(path))
+    fn load_opening_book(&mut self, path: &str) -> PyResult<bool> {
+        Ok(self.0.engine_mut().solver_mut()?.load_opening_book(Path::new(path)))
+    }
+
+    /// Sets a callback which receives progress reports while the AI player searches for a move.
+    ///
+    /// The callback is called with a dictionary describing the search's progress at each
+    /// iteration of the binary search, and after every `node_interval` explored nodes.
+    #[pyo3(signature=(callback, node_interval=1 << 20))]
+    fn set_progress_callback(&mut self, callback: Py<PyAny>, node_interval: usize) -> PyResult<()> {
+        self.0.engine_mut().solver_mut()?.set_observer(PyCallbackObserver::new(callback, node_interval));
+        Ok(())
+    }
+
+    /// The handicap applied to the AI player's moves, which stops it from scoring or selecting
+    /// any moves the handicap doesn't allow.
+    #[getter]
+    fn get_handicap(&self) -> PyHandicap {
+        PyHandicap(self.0.handicap())
+    }
+
+    #[setter]
+    fn set_handicap(&mut self, handicap: PyHandicap) {
+        self.0.set_handicap(handicap.0);
     }
 
-    /// Resets the AI player's solver.
+    /// Resets the AI player's engine.
     fn reset(&mut self) {
         self.0.reset();
     }
 
-    /// Solves a position to find its exact score using the AI player's solver.
+    /// Evaluates a position to find its score using the AI player's engine. With the default
+    /// solver, this is the position's exact score.
     fn solve(&mut self, position: &PyPosition) -> i8 {
         self.0.solve(&position.0)
     }
 
     /// Calculates the scores for all possible next moves in the given position using the
-    /// AI player's solver.
+    /// AI player's engine.
     pub fn get_all_move_scores(&mut self, position: &PyPosition) -> Vec<Option<i8>> {
         self.0.get_all_move_scores(&position.0).to_vec()
     }
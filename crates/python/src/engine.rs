@@ -0,0 +1,150 @@
+//! Provides the engines used by Python AI players, which can be a solver or any Python object
+//! implementing the engine methods.
+
+use connect_four_ai::{Engine, Position, Solver};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use crate::position::PyPosition;
+
+/// An engine implemented by a Python object, such as a remote engine or a test double.
+///
+/// The object must provide `evaluate(position) -> int` and
+/// `move_scores(position) -> list[int | None]` methods, and may provide a `reset()` method and
+/// a `name` attribute. Any exceptions raised by the object are printed, after which the engine
+/// reports a draw or no possible moves.
+#[derive(Debug)]
+pub struct PyObjectEngine {
+    object: Py<PyAny>,
+    name: String,
+}
+
+impl PyObjectEngine {
+    /// Creates a new engine for the given object, checking that it provides the required methods.
+    pub fn new(py: Python, object: Py<PyAny>) -> PyResult<PyObjectEngine> {
+        let bound = object.bind(py);
+        for method in ["evaluate", "move_scores"] {
+            if !bound.hasattr(method)? {
+                return Err(PyTypeError::new_err(format!("Engine objects must provide a '{method}' method.")));
+            }
+        }
+
+        let name = match bound.getattr("name") {
+            Ok(name) => name.extract::<String>()?,
+            Err(_) => bound.get_type().name()?.to_string(),
+        };
+        Ok(PyObjectEngine { object, name })
+    }
+
+    /// Calls a method of the engine object with a position, printing any raised exceptions.
+    fn call_with_position<T>(&self, method: &str, position: &Position, extract: impl FnOnce(&Bound<PyAny>) -> PyResult<T>) -> Option<T> {
+        Python::with_gil(|py| {
+            let result = self.object
+                .call_method1(py, method, (PyPosition(*position),))
+                .and_then(|value| extract(value.bind(py)));
+
+            result.map_err(|error| error.print(py)).ok()
+        })
+    }
+}
+
+impl Engine for PyObjectEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        self.call_with_position("evaluate", position, |value| value.extract()).unwrap_or(0)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.call_with_position("move_scores", position, |value| {
+            value.extract::<Vec<Option<i8>>>()?
+                .try_into()
+                .map_err(|_| PyValueError::new_err(format!("Move scores must contain exactly {} elements.", Position::WIDTH)))
+        })
+        .unwrap_or([None; Position::WIDTH])
+    }
+
+    fn reset(&mut self) {
+        Python::with_gil(|py| {
+            let bound = self.object.bind(py);
+            if bound.hasattr("reset").unwrap_or(false) {
+                if let Err(error) = bound.call_method0("reset") {
+                    error.print(py);
+                }
+            }
+        });
+    }
+}
+
+/// The engine used by a Python AI player, which is either the default solver or a Python object.
+#[derive(Debug)]
+pub enum PlayerEngine {
+    Solver(Box<Solver>),
+    Object(PyObjectEngine),
+}
+
+impl PlayerEngine {
+    /// Returns a reference to the engine's solver, or an error if it is a Python object.
+    pub fn solver(&self) -> PyResult<&Solver> {
+        match self {
+            PlayerEngine::Solver(solver) => Ok(solver),
+            PlayerEngine::Object(_) => Err(Self::not_a_solver()),
+        }
+    }
+
+    /// Returns a mutable reference to the engine's solver, or an error if it is a Python object.
+    pub fn solver_mut(&mut self) -> PyResult<&mut Solver> {
+        match self {
+            PlayerEngine::Solver(solver) => Ok(solver),
+            PlayerEngine::Object(_) => Err(Self::not_a_solver()),
+        }
+    }
+
+    /// The error raised when a solver-specific setting is used with a Python engine object.
+    fn not_a_solver() -> PyErr {
+        PyTypeError::new_err("This setting is only available for AI players using the default solver.")
+    }
+
+    /// Returns the engine as a trait object.
+    fn engine(&self) -> &dyn Engine {
+        match self {
+            PlayerEngine::Solver(solver) => solver,
+            PlayerEngine::Object(object) => object,
+        }
+    }
+
+    /// Returns the engine as a mutable trait object.
+    fn engine_mut(&mut self) -> &mut dyn Engine {
+        match self {
+            PlayerEngine::Solver(solver) => solver,
+            PlayerEngine::Object(object) => object,
+        }
+    }
+}
+
+impl Engine for PlayerEngine {
+    fn name(&self) -> &str {
+        self.engine().name()
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        self.engine_mut().evaluate(position)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.engine_mut().move_scores(position)
+    }
+
+    fn move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        self.engine_mut().move_scores_with_hint(position, expected_score)
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        self.engine_mut().best_move(position)
+    }
+
+    fn reset(&mut self) {
+        self.engine_mut().reset()
+    }
+}
\ No newline at end of file
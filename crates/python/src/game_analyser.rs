@@ -0,0 +1,89 @@
+//! Provides an analyser for annotating complete Connect Four games.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use connect_four_ai::{GameAnalyser, GameReport};
+use crate::solver::build_solver;
+
+/// An analyser which replays a game, solving every possible move at each ply to annotate the
+/// quality of the moves which were played.
+///
+/// Each move is classified as best, good, an inaccuracy, a mistake or a blunder, by comparing
+/// its outcome and score with the best move available. The analyser's solver keeps its
+/// transposition table between plies and games.
+#[pyclass(name="GameAnalyser")]
+#[derive(Debug)]
+pub struct PyGameAnalyser(GameAnalyser);
+
+#[pymethods]
+impl PyGameAnalyser {
+    /// Creates a new game analyser.
+    ///
+    /// If `parallel` is true, the possible moves at each ply are evaluated concurrently. If
+    /// `transposition_table_memory` is given, the solver's transposition table is sized to
+    /// fit within that many megabytes, instead of the default of around 64 MB.
+    #[new]
+    #[pyo3(signature=(parallel=false, transposition_table_memory=None))]
+    fn new(parallel: bool, transposition_table_memory: Option<usize>) -> PyResult<PyGameAnalyser> {
+        let mut solver = build_solver(transposition_table_memory)?;
+        solver.parallel_move_scores = parallel;
+        Ok(PyGameAnalyser(GameAnalyser::with_solver(solver)))
+    }
+
+    /// The largest score loss for which a move keeping the best outcome is classified as
+    /// good rather than an inaccuracy.
+    #[getter]
+    fn get_good_score_loss(&self) -> i8 {
+        self.0.good_score_loss
+    }
+
+    #[setter]
+    fn set_good_score_loss(&mut self, score_loss: i8) {
+        self.0.good_score_loss = score_loss;
+    }
+
+    /// Resets the analyser's solver.
+    fn reset(&mut self) {
+        self.0.solver.reset();
+    }
+
+    /// Analyses a game given as a string of 1-indexed moves, returning a report annotating
+    /// each of its moves.
+    ///
+    /// The report is a dictionary containing the game's `moves`, a list of `annotations`,
+    /// the `accuracy` of each player as a list of two percentages, and the `winner` (1, 2 or
+    /// `None`). Each annotation is a dictionary containing the move's `ply`, `player`,
+    /// `column`, `score`, `best_score`, `best_moves`, `accuracy` and `classification`.
+    fn analyse<'py>(&mut self, py: Python<'py>, moves: &str) -> PyResult<Bound<'py, PyDict>> {
+        let report = self.0.analyse(moves)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        report_to_dict(py, &report)
+    }
+}
+
+/// Converts a game report into a Python dictionary.
+fn report_to_dict<'py>(py: Python<'py>, report: &GameReport) -> PyResult<Bound<'py, PyDict>> {
+    let annotations = report.annotations
+        .iter()
+        .map(|annotation| {
+            let info = PyDict::new(py);
+            info.set_item("ply", annotation.ply)?;
+            info.set_item("player", annotation.player)?;
+            info.set_item("column", annotation.column)?;
+            info.set_item("score", annotation.score)?;
+            info.set_item("best_score", annotation.best_score)?;
+            info.set_item("best_moves", annotation.best_moves.clone())?;
+            info.set_item("accuracy", annotation.accuracy)?;
+            info.set_item("classification", annotation.classification.to_string().to_lowercase())?;
+            Ok(info)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let info = PyDict::new(py);
+    info.set_item("moves", &report.moves)?;
+    info.set_item("annotations", annotations)?;
+    info.set_item("accuracy", report.accuracy.to_vec())?;
+    info.set_item("winner", report.winner)?;
+    Ok(info)
+}
\ No newline at end of file
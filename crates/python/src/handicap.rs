@@ -0,0 +1,73 @@
+//! Provides a catalogue of handicaps, which give a human player odds against an AI player by
+//! changing the game's starting position or restricting the AI's opening move.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use connect_four_ai::Handicap;
+use crate::position::PyPosition;
+use crate::solver::PySolver;
+
+/// A handicap for a game between a human and an AI player.
+///
+/// Each handicap decides the game's starting position, which player moves first, and any
+/// restriction on the AI's opening move. A game without a handicap starts from the empty
+/// board with the AI moving first.
+#[pyclass(name="Handicap")]
+#[derive(Copy, Clone, Debug)]
+pub struct PyHandicap(pub (crate) Handicap);
+
+#[pymethods]
+impl PyHandicap {
+    /// The standard game, in which the AI moves first.
+    #[classattr]
+    const NONE: Self = Self(Handicap::None);
+    /// The standard game, in which the human moves first.
+    #[classattr]
+    const AI_MOVES_SECOND: Self = Self(Handicap::AIMovesSecond);
+    /// The AI moves first, but must open in one of the edge columns.
+    #[classattr]
+    const EDGE_OPENING: Self = Self(Handicap::EdgeOpening);
+
+    /// The human moves first, with their opening disc played in the given 0-based column,
+    /// after which the AI moves.
+    #[staticmethod]
+    fn human_opening(column: usize) -> PyResult<PyHandicap> {
+        let handicap = Handicap::HumanOpening(column);
+        handicap.validate()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyHandicap(handicap))
+    }
+
+    /// Returns the catalogue of handicaps, from no handicap to the strongest.
+    #[staticmethod]
+    fn all() -> Vec<PyHandicap> {
+        Handicap::ALL.into_iter().map(PyHandicap).collect()
+    }
+
+    /// Returns a description of the handicap.
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Whether the human player makes the first move of the game.
+    #[getter]
+    fn get_human_moves_first(&self) -> bool {
+        self.0.human_moves_first()
+    }
+
+    /// Returns the starting position of a game with the handicap, whose current player is
+    /// the human if `human_moves_first` is true, and the AI otherwise.
+    fn position(&self) -> PyResult<PyPosition> {
+        self.0.position()
+            .map(PyPosition)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Finds the theoretical outcome of a game with the handicap from the human's
+    /// perspective, which is one of 'win', 'draw' or 'loss'.
+    fn outcome(&self, solver: &mut PySolver) -> PyResult<String> {
+        self.0.outcome(&mut solver.0)
+            .map(|outcome| format!("{outcome:?}").to_lowercase())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
\ No newline at end of file
@@ -3,6 +3,10 @@
 mod position;
 mod solver;
 mod ai_player;
+mod search_observer;
+mod engine;
+mod game_analyser;
+mod handicap;
 
 use pyo3::prelude::*;
 
@@ -23,4 +27,10 @@ mod connect_four_ai {
 
     #[pymodule_export]
     use crate::ai_player::PyDifficulty;
+
+    #[pymodule_export]
+    use crate::game_analyser::PyGameAnalyser;
+
+    #[pymodule_export]
+    use crate::handicap::PyHandicap;
 }
\ No newline at end of file
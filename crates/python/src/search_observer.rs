@@ -0,0 +1,57 @@
+//! Provides an observer for reporting the progress of a solver's search to a Python callback.
+
+use connect_four_ai::{SearchObserver, SearchProgress};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// A search observer which passes progress reports to a Python callback.
+///
+/// The callback is called with a single dictionary argument, containing the `iteration`, `min`,
+/// `max` and `mid` values of the current search window, the number of `explored_positions`,
+/// and the `elapsed` time of the search in seconds.
+pub struct PyCallbackObserver {
+    callback: Py<PyAny>,
+    node_interval: usize,
+}
+
+impl PyCallbackObserver {
+    /// Creates a new observer for the given callback, which is called at each iteration of the
+    /// search and after every `node_interval` explored nodes.
+    pub fn new(callback: Py<PyAny>, node_interval: usize) -> PyCallbackObserver {
+        PyCallbackObserver { callback, node_interval }
+    }
+
+    /// Calls the callback with the given progress, printing any raised exceptions.
+    fn report(&self, progress: &SearchProgress) {
+        Python::with_gil(|py| {
+            let result = (|| {
+                let info = PyDict::new(py);
+                info.set_item("iteration", progress.iteration)?;
+                info.set_item("min", progress.min)?;
+                info.set_item("max", progress.max)?;
+                info.set_item("mid", progress.mid)?;
+                info.set_item("explored_positions", progress.explored_positions)?;
+                info.set_item("elapsed", progress.elapsed.as_secs_f64())?;
+                self.callback.call1(py, (info,))
+            })();
+
+            if let Err(error) = result {
+                error.print(py);
+            }
+        });
+    }
+}
+
+impl SearchObserver for PyCallbackObserver {
+    fn on_iteration(&mut self, progress: &SearchProgress) {
+        self.report(progress);
+    }
+
+    fn on_nodes(&mut self, progress: &SearchProgress) {
+        self.report(progress);
+    }
+
+    fn node_interval(&self) -> usize {
+        self.node_interval
+    }
+}
\ No newline at end of file
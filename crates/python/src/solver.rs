@@ -2,8 +2,10 @@
 
 use std::path::Path;
 use pyo3::prelude::*;
-use connect_four_ai::Solver;
+use connect_four_ai::{Engine, Solver, SolverBuilder};
+use pyo3::exceptions::PyValueError;
 use crate::position::PyPosition;
+use crate::search_observer::PyCallbackObserver;
 
 /// A strong solver for finding the exact score of Connect Four positions.
 ///
@@ -15,14 +17,18 @@ use crate::position::PyPosition;
 /// - A binary search on the score for faster convergence
 #[pyclass(name="Solver")]
 #[derive(Debug)]
-pub struct PySolver(Solver);
+pub struct PySolver(pub (crate) Solver);
 
 #[pymethods]
 impl PySolver {
     /// Creates a new `Solver` instance, using the pre-packaged opening book.
+    ///
+    /// If `transposition_table_memory` is given, the solver's transposition table is sized to
+    /// fit within that many megabytes, instead of the default of around 64 MB.
     #[new]
-    fn new() -> PySolver {
-        PySolver(Solver::new())
+    #[pyo3(signature=(transposition_table_memory=None))]
+    fn new(transposition_table_memory: Option<usize>) -> PyResult<PySolver> {
+        Ok(PySolver(build_solver(transposition_table_memory)?))
     }
 
     /// A counter for the number of nodes explored in the last `solve` call.
@@ -31,6 +37,26 @@ impl PySolver {
         self.0.explored_positions
     }
 
+    /// The number of entries in the solver's transposition table.
+    #[getter]
+    fn get_transposition_table_size(&self) -> usize {
+        self.0.transposition_table.size()
+    }
+
+    /// Whether `reset` releases the memory used by the solver's transposition table.
+    ///
+    /// The table is allocated by the first search which needs it, so releasing it stops idle
+    /// solvers from holding on to its memory.
+    #[getter]
+    fn get_release_table_on_reset(&self) -> bool {
+        self.0.release_table_on_reset
+    }
+
+    #[setter]
+    fn set_release_table_on_reset(&mut self, release: bool) {
+        self.0.release_table_on_reset = release;
+    }
+
     /// Attempts to load an opening book from the given path.
     ///
     /// Returns whether the opening book was successfully loaded.
@@ -38,6 +64,15 @@ impl PySolver {
         self.0.load_opening_book(Path::new(path))
     }
 
+    /// Sets a callback which receives progress reports during each search.
+    ///
+    /// The callback is called with a dictionary describing the search's progress at each
+    /// iteration of the binary search, and after every `node_interval` explored nodes.
+    #[pyo3(signature=(callback, node_interval=1 << 20))]
+    fn set_progress_callback(&mut self, callback: Py<PyAny>, node_interval: usize) {
+        self.0.set_observer(PyCallbackObserver::new(callback, node_interval));
+    }
+
     /// Resets the solver's state.
     fn reset(&mut self) {
         self.0.reset();
@@ -67,6 +102,19 @@ impl PySolver {
         self.0.solve(&position.0)
     }
 
+    /// Solves a position to find its exact score, starting from a narrow window around an
+    /// expected score.
+    ///
+    /// The exact score is always found, but it is found fastest when the hint is correct.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The board position to solve.
+    /// * `expected_score`: The expected score of the position.
+    fn solve_with_hint(&mut self, position: &PyPosition, expected_score: i8) -> i8 {
+        self.0.solve_with_hint(&position.0, expected_score)
+    }
+
     /// Calculates the scores for all possible next moves in the given position.
     ///
     /// Returns a fixed-size array where each index corresponds to a column, containing
@@ -77,4 +125,40 @@ impl PySolver {
     fn get_all_move_scores(&mut self, position: &PyPosition) -> Vec<Option<i8>> {
         self.0.get_all_move_scores(&position.0).to_vec()
     }
+
+    /// The name of the solver as an engine, allowing solvers to be used as AI player engines.
+    #[getter]
+    fn get_name(&self) -> String {
+        Engine::name(&self.0).to_string()
+    }
+
+    /// Evaluates a position to find its exact score, which is equivalent to `solve`.
+    fn evaluate(&mut self, position: &PyPosition) -> i8 {
+        self.0.evaluate(&position.0)
+    }
+
+    /// Calculates the scores for all possible next moves in the given position, which is
+    /// equivalent to `get_all_move_scores`.
+    fn move_scores(&mut self, position: &PyPosition) -> Vec<Option<i8>> {
+        self.0.move_scores(&position.0).to_vec()
+    }
+
+    /// Returns the optimal move in the given position, or `None` if no moves are possible.
+    ///
+    /// Ties are broken in favour of columns closer to the centre.
+    fn best_move(&mut self, position: &PyPosition) -> Option<usize> {
+        self.0.best_move(&position.0)
+    }
+}
+
+/// Creates a solver using the pre-packaged opening book, with an optional memory budget for its
+/// transposition table in megabytes.
+pub fn build_solver(transposition_table_memory: Option<usize>) -> PyResult<Solver> {
+    match transposition_table_memory {
+        Some(megabytes) => SolverBuilder::new()
+            .transposition_table_memory(megabytes)
+            .build()
+            .map_err(|e| PyValueError::new_err(e.to_string())),
+        None => Ok(Solver::new()),
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-//! Provides the logic for an AI player which utilises a solver to select which move to play
+//! Provides the logic for an AI player which utilises an engine to select which move to play
 //! in a given Connect Four position.
 //!
 //! The AI's skill is determined by the `Difficulty` enum, which controls the temperature of
@@ -6,10 +6,14 @@
 //! of behaviours, from a more random 'Easy' player to a perfect, greedy 'Impossible' player.
 
 use std::path::Path;
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsError, JsValue};
 use wasm_bindgen::prelude::wasm_bindgen;
-use connect_four_ai::{AIPlayer, Difficulty, Position};
+use connect_four_ai::{AIPlayer, Difficulty, Engine, Position, Solver};
+use crate::engine::{JsObjectEngine, PlayerEngine};
+use crate::handicap::WASMHandicap;
 use crate::position::WASMPosition;
+use crate::search_observer::WASMProgressObserver;
+use crate::solver::build_solver;
 
 /// An enum to represent the difficulty of an AI player.
 #[wasm_bindgen(js_name=Difficulty)]
@@ -39,42 +43,112 @@ impl WASMDifficulty {
     }
 }
 
-/// An AI player that uses a solver to determine the best move to play in a Connect Four position.
+/// An AI player that uses an engine to determine the best move to play in a Connect Four
+/// position. By default, the engine is a perfect solver.
 ///
 /// The player's skill level can be configured using the `Difficulty` enum, which adjusts the
 /// move selection strategy.
 #[wasm_bindgen(js_name=AIPlayer)]
 #[derive(Debug)]
-pub struct WASMAIPlayer(AIPlayer);
+pub struct WASMAIPlayer(AIPlayer<PlayerEngine>);
 
 #[wasm_bindgen(js_class=AIPlayer)]
 impl WASMAIPlayer {
     /// Creates a new AI player with a default solver and specified difficulty.
     #[wasm_bindgen(constructor)]
     pub fn new(difficulty: WASMDifficulty) -> WASMAIPlayer {
-        WASMAIPlayer(AIPlayer::new(difficulty.0))
+        WASMAIPlayer(AIPlayer::with_engine(difficulty.0, PlayerEngine::Solver(Box::new(Solver::new()))))
+    }
+
+    /// Creates a new AI player with the specified difficulty, whose solver uses a transposition
+    /// table sized to fit within the given number of megabytes.
+    #[wasm_bindgen(js_name=withMemory)]
+    pub fn with_memory(difficulty: WASMDifficulty, megabytes: usize) -> Result<WASMAIPlayer, JsError> {
+        Ok(WASMAIPlayer(AIPlayer::with_engine(difficulty.0, PlayerEngine::Solver(Box::new(build_solver(megabytes)?)))))
+    }
+
+    /// Creates a new AI player with the specified difficulty, which uses the given JavaScript
+    /// object to score its moves instead of the default solver.
+    ///
+    /// The object must provide `evaluate(position)` and `moveScores(position)` methods, and may
+    /// provide a `reset()` method and a `name` property.
+    #[wasm_bindgen(js_name=withEngine)]
+    pub fn with_engine(difficulty: WASMDifficulty, engine: JsValue) -> Result<WASMAIPlayer, JsError> {
+        Ok(WASMAIPlayer(AIPlayer::with_engine(difficulty.0, PlayerEngine::Object(JsObjectEngine::new(engine)?))))
+    }
+
+    /// The name of the AI player's engine.
+    #[wasm_bindgen(getter, js_name=engineName)]
+    pub fn engine_name(&self) -> String {
+        self.0.engine().name().to_string()
+    }
+
+    /// The number of entries in the AI player's transposition table.
+    #[wasm_bindgen(getter, js_name=transpositionTableSize)]
+    pub fn transposition_table_size(&self) -> Result<usize, JsError> {
+        Ok(self.0.engine().solver()?.transposition_table.size())
+    }
+
+    /// Whether `reset` releases the memory used by the AI player's transposition table.
+    ///
+    /// The table is allocated by the first search which needs it, so releasing it stops idle
+    /// players from holding on to its memory.
+    #[wasm_bindgen(getter, js_name=releaseTableOnReset)]
+    pub fn release_table_on_reset(&self) -> Result<bool, JsError> {
+        Ok(self.0.engine().solver()?.release_table_on_reset)
+    }
+
+    #[wasm_bindgen(setter, js_name=releaseTableOnReset)]
+    pub fn set_release_table_on_reset(&mut self, release: bool) -> Result<(), JsError> {
+        self.0.engine_mut().solver_mut()?.release_table_on_reset = release;
+        Ok(())
     }
 
     /// Attempts to load an opening book from the given path for the AI player's solver.
     ///
     /// Returns whether the opening book was successfully loaded.
     #[wasm_bindgen(js_name=loadOpeningBook)]
-    pub fn load_opening_book(&mut self, path: &str) -> bool {
-        self.0.load_opening_book(Path::new(path))
+    pub fn load_opening_book(&mut self, path: &str) -> Result<bool, JsError> {
+        Ok(self.0.engine_mut().solver_mut()?.load_opening_book(Path::new(path)))
+    }
+
+    /// Enables progress messages, which are posted using the global `postMessage` function
+    /// while the AI player searches for a move. This is intended for players running inside
+    /// a Web Worker.
+    ///
+    /// A message is posted at each iteration of the binary search, and after every
+    /// `nodeInterval` explored nodes.
+    #[wasm_bindgen(js_name=enableProgressMessages)]
+    pub fn enable_progress_messages(&mut self, node_interval: usize) -> Result<(), JsError> {
+        self.0.engine_mut().solver_mut()?.set_observer(WASMProgressObserver::new(node_interval));
+        Ok(())
+    }
+
+    /// The handicap applied to the AI player's moves, which stops it from scoring or selecting
+    /// any moves the handicap doesn't allow.
+    #[wasm_bindgen(getter)]
+    pub fn handicap(&self) -> WASMHandicap {
+        WASMHandicap(self.0.handicap())
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_handicap(&mut self, handicap: WASMHandicap) {
+        self.0.set_handicap(handicap.0);
     }
 
-    /// Resets the AI player's solver.
+    /// Resets the AI player's engine.
     pub fn reset(&mut self) {
         self.0.reset();
     }
 
-    /// Solves a position to find its exact score using the AI player's solver.
+    /// Evaluates a position to find its score using the AI player's engine. With the default
+    /// solver, this is the position's exact score.
     pub fn solve(&mut self, position: &WASMPosition) -> i8 {
         self.0.solve(&position.0)
     }
 
     /// Calculates the scores for all possible next moves in the given position using the
-    /// AI player's solver.
+    /// AI player's engine.
     #[wasm_bindgen(js_name=getAllMoveScores)]
     pub fn get_all_move_scores(&mut self, position: &WASMPosition) -> Vec<JsValue> {
         self.0.get_all_move_scores(&position.0)
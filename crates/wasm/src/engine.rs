@@ -0,0 +1,162 @@
+//! Provides the engines used by JavaScript AI players, which can be a solver or any JavaScript
+//! object implementing the engine methods.
+
+use connect_four_ai::{Engine, Position, Solver};
+use js_sys::{Array, Function, Reflect};
+use wasm_bindgen::prelude::*;
+use crate::position::WASMPosition;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `console.error` function, used to report errors raised by engine objects.
+    #[wasm_bindgen(js_namespace=console, js_name=error)]
+    fn console_error(value: &JsValue);
+}
+
+/// An engine implemented by a JavaScript object, such as a remote engine or a test double.
+///
+/// The object must provide `evaluate(position)` and `moveScores(position)` methods, returning
+/// a score and an array of scores (with `null` for full columns), and may provide a `reset()`
+/// method and a `name` property. Any errors thrown by the object are logged to the console,
+/// after which the engine reports a draw or no possible moves.
+#[derive(Debug)]
+pub struct JsObjectEngine {
+    object: JsValue,
+    name: String,
+}
+
+impl JsObjectEngine {
+    /// Creates a new engine for the given object, checking that it provides the required methods.
+    pub fn new(object: JsValue) -> Result<JsObjectEngine, JsError> {
+        for method in ["evaluate", "moveScores"] {
+            if Self::method(&object, method).is_none() {
+                return Err(JsError::new(&format!("Engine objects must provide a '{method}' method.")));
+            }
+        }
+
+        let name = Reflect::get(&object, &JsValue::from_str("name"))
+            .ok()
+            .and_then(|name| name.as_string())
+            .unwrap_or_else(|| String::from("Engine"));
+        Ok(JsObjectEngine { object, name })
+    }
+
+    /// Returns the method of an object with the given name, if it exists.
+    fn method(object: &JsValue, name: &str) -> Option<Function> {
+        Reflect::get(object, &JsValue::from_str(name))
+            .ok()
+            .and_then(|method| method.dyn_into::<Function>().ok())
+    }
+
+    /// Calls a method of the engine object with a position, logging any thrown errors.
+    fn call_with_position(&self, method: &str, position: &Position) -> Option<JsValue> {
+        let result = Self::method(&self.object, method)?
+            .call1(&self.object, &JsValue::from(WASMPosition(*position)));
+
+        result.map_err(|error| console_error(&error)).ok()
+    }
+}
+
+impl Engine for JsObjectEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        self.call_with_position("evaluate", position)
+            .and_then(|score| score.as_f64())
+            .map_or(0, |score| score as i8)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        let mut scores = [None; Position::WIDTH];
+        if let Some(values) = self.call_with_position("moveScores", position) {
+            for (column, value) in Array::from(&values).iter().take(Position::WIDTH).enumerate() {
+                scores[column] = value.as_f64().map(|score| score as i8);
+            }
+        }
+        scores
+    }
+
+    fn reset(&mut self) {
+        if let Some(reset) = Self::method(&self.object, "reset") {
+            if let Err(error) = reset.call0(&self.object) {
+                console_error(&error);
+            }
+        }
+    }
+}
+
+/// The engine used by a JavaScript AI player, which is either the default solver or a
+/// JavaScript object.
+#[derive(Debug)]
+pub enum PlayerEngine {
+    Solver(Box<Solver>),
+    Object(JsObjectEngine),
+}
+
+impl PlayerEngine {
+    /// Returns a reference to the engine's solver, or an error if it is a JavaScript object.
+    pub fn solver(&self) -> Result<&Solver, JsError> {
+        match self {
+            PlayerEngine::Solver(solver) => Ok(solver),
+            PlayerEngine::Object(_) => Err(Self::not_a_solver()),
+        }
+    }
+
+    /// Returns a mutable reference to the engine's solver, or an error if it is a JavaScript
+    /// object.
+    pub fn solver_mut(&mut self) -> Result<&mut Solver, JsError> {
+        match self {
+            PlayerEngine::Solver(solver) => Ok(solver),
+            PlayerEngine::Object(_) => Err(Self::not_a_solver()),
+        }
+    }
+
+    /// The error thrown when a solver-specific setting is used with a JavaScript engine object.
+    fn not_a_solver() -> JsError {
+        JsError::new("This setting is only available for AI players using the default solver.")
+    }
+
+    /// Returns the engine as a trait object.
+    fn engine(&self) -> &dyn Engine {
+        match self {
+            PlayerEngine::Solver(solver) => solver,
+            PlayerEngine::Object(object) => object,
+        }
+    }
+
+    /// Returns the engine as a mutable trait object.
+    fn engine_mut(&mut self) -> &mut dyn Engine {
+        match self {
+            PlayerEngine::Solver(solver) => solver,
+            PlayerEngine::Object(object) => object,
+        }
+    }
+}
+
+impl Engine for PlayerEngine {
+    fn name(&self) -> &str {
+        self.engine().name()
+    }
+
+    fn evaluate(&mut self, position: &Position) -> i8 {
+        self.engine_mut().evaluate(position)
+    }
+
+    fn move_scores(&mut self, position: &Position) -> [Option<i8>; Position::WIDTH] {
+        self.engine_mut().move_scores(position)
+    }
+
+    fn move_scores_with_hint(&mut self, position: &Position, expected_score: i8) -> [Option<i8>; Position::WIDTH] {
+        self.engine_mut().move_scores_with_hint(position, expected_score)
+    }
+
+    fn best_move(&mut self, position: &Position) -> Option<usize> {
+        self.engine_mut().best_move(position)
+    }
+
+    fn reset(&mut self) {
+        self.engine_mut().reset()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,105 @@
+//! Provides an analyser for annotating complete Connect Four games.
+
+use connect_four_ai::{GameAnalyser, GameReport, MoveAnnotation, Solver};
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use crate::solver::build_solver;
+
+/// An analyser which replays a game, solving every possible move at each ply to annotate the
+/// quality of the moves which were played.
+///
+/// Each move is classified as best, good, an inaccuracy, a mistake or a blunder, by comparing
+/// its outcome and score with the best move available. The analyser's solver keeps its
+/// transposition table between plies and games.
+#[wasm_bindgen(js_name=GameAnalyser)]
+#[derive(Debug)]
+pub struct WASMGameAnalyser(GameAnalyser);
+
+#[wasm_bindgen(js_class=GameAnalyser)]
+impl WASMGameAnalyser {
+    /// Creates a new game analyser, using the pre-packaged opening book.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WASMGameAnalyser {
+        WASMGameAnalyser(GameAnalyser::with_solver(Solver::new()))
+    }
+
+    /// Creates a new game analyser, whose solver uses a transposition table sized to fit
+    /// within the given number of megabytes.
+    #[wasm_bindgen(js_name=withMemory)]
+    pub fn with_memory(megabytes: usize) -> Result<WASMGameAnalyser, JsError> {
+        Ok(WASMGameAnalyser(GameAnalyser::with_solver(build_solver(megabytes)?)))
+    }
+
+    /// The largest score loss for which a move keeping the best outcome is classified as
+    /// good rather than an inaccuracy.
+    #[wasm_bindgen(getter, js_name=goodScoreLoss)]
+    pub fn good_score_loss(&self) -> i8 {
+        self.0.good_score_loss
+    }
+
+    #[wasm_bindgen(setter, js_name=goodScoreLoss)]
+    pub fn set_good_score_loss(&mut self, score_loss: i8) {
+        self.0.good_score_loss = score_loss;
+    }
+
+    /// Resets the analyser's solver.
+    pub fn reset(&mut self) {
+        self.0.solver.reset();
+    }
+
+    /// Analyses a game given as a string of 1-indexed moves, returning a report annotating
+    /// each of its moves.
+    ///
+    /// The report is an object of the form `{ moves, annotations, accuracy, winner }`, where
+    /// `accuracy` holds each player's accuracy as a percentage and `winner` is 1, 2 or `null`.
+    /// Each annotation is an object of the form `{ ply, player, column, score, bestScore,
+    /// bestMoves, accuracy, classification }`.
+    pub fn analyse(&mut self, moves: &str) -> Result<JsValue, JsError> {
+        let report = self.0.analyse(moves)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(report_to_object(&report).into())
+    }
+}
+
+/// Sets each of the given fields on a new JavaScript object.
+fn to_object<const N: usize>(fields: [(&str, JsValue); N]) -> Object {
+    let object = Object::new();
+    for (key, value) in fields {
+        let _ = Reflect::set(&object, &JsValue::from_str(key), &value);
+    }
+    object
+}
+
+/// Converts a move annotation into a JavaScript object.
+fn annotation_to_object(annotation: &MoveAnnotation) -> Object {
+    let best_moves: Array = annotation.best_moves.iter().map(|&column| JsValue::from(column)).collect();
+    to_object([
+        ("ply", JsValue::from(annotation.ply)),
+        ("player", JsValue::from(annotation.player)),
+        ("column", JsValue::from(annotation.column)),
+        ("score", JsValue::from(annotation.score)),
+        ("bestScore", JsValue::from(annotation.best_score)),
+        ("bestMoves", best_moves.into()),
+        ("accuracy", JsValue::from(annotation.accuracy)),
+        ("classification", JsValue::from_str(&annotation.classification.to_string().to_lowercase())),
+    ])
+}
+
+/// Converts a game report into a JavaScript object.
+fn report_to_object(report: &GameReport) -> Object {
+    let annotations: Array = report.annotations.iter().map(annotation_to_object).collect();
+    let accuracy: Array = report.accuracy.iter().map(|&accuracy| JsValue::from(accuracy)).collect();
+    to_object([
+        ("moves", JsValue::from_str(&report.moves)),
+        ("annotations", annotations.into()),
+        ("accuracy", accuracy.into()),
+        ("winner", report.winner.map_or(JsValue::NULL, JsValue::from)),
+    ])
+}
+
+/// Default constructor for the `WASMGameAnalyser` struct.
+impl Default for WASMGameAnalyser {
+    fn default() -> WASMGameAnalyser {
+        WASMGameAnalyser::new()
+    }
+}
\ No newline at end of file
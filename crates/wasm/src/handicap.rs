@@ -0,0 +1,81 @@
+//! Provides a catalogue of handicaps, which give a human player odds against an AI player by
+//! changing the game's starting position or restricting the AI's opening move.
+
+use connect_four_ai::Handicap;
+use wasm_bindgen::prelude::*;
+use crate::position::WASMPosition;
+use crate::solver::WASMSolver;
+
+/// A handicap for a game between a human and an AI player.
+///
+/// Each handicap decides the game's starting position, which player moves first, and any
+/// restriction on the AI's opening move. A game without a handicap starts from the empty
+/// board with the AI moving first.
+#[wasm_bindgen(js_name=Handicap)]
+#[derive(Copy, Clone, Debug)]
+pub struct WASMHandicap(pub (crate) Handicap);
+
+#[wasm_bindgen(js_class=Handicap)]
+impl WASMHandicap {
+    /// The standard game, in which the AI moves first.
+    #[wasm_bindgen(getter, js_name=NONE)]
+    pub fn none() -> WASMHandicap {
+        WASMHandicap(Handicap::None)
+    }
+
+    /// The standard game, in which the human moves first.
+    #[wasm_bindgen(getter, js_name=AI_MOVES_SECOND)]
+    pub fn ai_moves_second() -> WASMHandicap {
+        WASMHandicap(Handicap::AIMovesSecond)
+    }
+
+    /// The AI moves first, but must open in one of the edge columns.
+    #[wasm_bindgen(getter, js_name=EDGE_OPENING)]
+    pub fn edge_opening() -> WASMHandicap {
+        WASMHandicap(Handicap::EdgeOpening)
+    }
+
+    /// The human moves first, with their opening disc played in the given 0-based column,
+    /// after which the AI moves.
+    #[wasm_bindgen(js_name=humanOpening)]
+    pub fn human_opening(column: usize) -> Result<WASMHandicap, JsError> {
+        let handicap = Handicap::HumanOpening(column);
+        handicap.validate()
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WASMHandicap(handicap))
+    }
+
+    /// Returns the catalogue of handicaps, from no handicap to the strongest.
+    pub fn all() -> Vec<WASMHandicap> {
+        Handicap::ALL.into_iter().map(WASMHandicap).collect()
+    }
+
+    /// Returns a description of the handicap.
+    #[wasm_bindgen(js_name=toString)]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Whether the human player makes the first move of the game.
+    #[wasm_bindgen(getter, js_name=humanMovesFirst)]
+    pub fn human_moves_first(&self) -> bool {
+        self.0.human_moves_first()
+    }
+
+    /// Returns the starting position of a game with the handicap, whose current player is
+    /// the human if `humanMovesFirst` is true, and the AI otherwise.
+    pub fn position(&self) -> Result<WASMPosition, JsError> {
+        self.0.position()
+            .map(WASMPosition)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Finds the theoretical outcome of a game with the handicap from the human's
+    /// perspective, which is one of 'win', 'draw' or 'loss'.
+    pub fn outcome(&self, solver: &mut WASMSolver) -> Result<String, JsError> {
+        self.0.outcome(&mut solver.0)
+            .map(|outcome| format!("{outcome:?}").to_lowercase())
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+}
\ No newline at end of file
@@ -3,11 +3,17 @@
 mod position;
 mod solver;
 mod ai_player;
+mod search_observer;
+mod engine;
+mod game_analyser;
+mod handicap;
 
 use wasm_bindgen::prelude::*;
 pub use position::WASMPosition;
 pub use solver::WASMSolver;
 pub use ai_player::{WASMDifficulty, WASMAIPlayer};
+pub use game_analyser::WASMGameAnalyser;
+pub use handicap::WASMHandicap;
 
 /// Sets up a hook to log Rust panics to the browser's console when the
 /// WASM module is first loaded.
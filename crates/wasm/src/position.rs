@@ -73,6 +73,7 @@ impl WASMPosition {
 
     /// Returns the Position's attributes formatted as a string.
     #[wasm_bindgen(js_name=toString)]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         format!("{:?}", self.0)
     }
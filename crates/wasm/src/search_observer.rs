@@ -0,0 +1,62 @@
+//! Provides an observer for reporting the progress of a solver's search as JavaScript messages.
+
+use connect_four_ai::{SearchObserver, SearchProgress};
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `postMessage` function, used to send messages from a Web Worker.
+    #[wasm_bindgen(js_name=postMessage)]
+    fn post_message(message: &JsValue);
+}
+
+/// A search observer which posts progress reports using the global `postMessage` function.
+///
+/// This is intended for solvers running inside a Web Worker, allowing the page to display
+/// the solver's progress without blocking. Each message is an object of the form
+/// `{ type: "progress", iteration, min, max, mid, exploredPositions, elapsed }`, where
+/// `elapsed` is the time since the search started in milliseconds.
+pub struct WASMProgressObserver {
+    node_interval: usize,
+}
+
+impl WASMProgressObserver {
+    /// Creates a new observer which posts a message at each iteration of the search and after
+    /// every `node_interval` explored nodes.
+    pub fn new(node_interval: usize) -> WASMProgressObserver {
+        WASMProgressObserver { node_interval }
+    }
+
+    /// Posts a message describing the given progress.
+    fn report(&self, progress: &SearchProgress) {
+        let message = Object::new();
+        let fields = [
+            ("type", JsValue::from_str("progress")),
+            ("iteration", JsValue::from(progress.iteration)),
+            ("min", JsValue::from(progress.min)),
+            ("max", JsValue::from(progress.max)),
+            ("mid", JsValue::from(progress.mid)),
+            ("exploredPositions", JsValue::from(progress.explored_positions)),
+            ("elapsed", JsValue::from(progress.elapsed.as_secs_f64() * 1000.0)),
+        ];
+        for (key, value) in fields {
+            let _ = Reflect::set(&message, &JsValue::from_str(key), &value);
+        }
+        post_message(&message);
+    }
+}
+
+impl SearchObserver for WASMProgressObserver {
+    fn on_iteration(&mut self, progress: &SearchProgress) {
+        self.report(progress);
+    }
+
+    fn on_nodes(&mut self, progress: &SearchProgress) {
+        self.report(progress);
+    }
+
+    fn node_interval(&self) -> usize {
+        self.node_interval
+    }
+}
\ No newline at end of file
@@ -1,7 +1,8 @@
 //! Provides the core solving logic for the Connect Four AI.
 
 use crate::position::WASMPosition;
-use connect_four_ai::Solver;
+use crate::search_observer::WASMProgressObserver;
+use connect_four_ai::{Engine, Solver, SolverBuilder};
 use std::path::Path;
 use wasm_bindgen::prelude::*;
 
@@ -15,7 +16,7 @@ use wasm_bindgen::prelude::*;
 /// - A binary search on the score for faster convergence
 #[wasm_bindgen(js_name=Solver)]
 #[derive(Debug)]
-pub struct WASMSolver(Solver);
+pub struct WASMSolver(pub (crate) Solver);
 
 #[wasm_bindgen(js_class=Solver)]
 impl WASMSolver {
@@ -25,12 +26,39 @@ impl WASMSolver {
         WASMSolver(Solver::new())
     }
 
+    /// Creates a new `Solver` instance, using the pre-packaged opening book and a
+    /// transposition table sized to fit within the given number of megabytes.
+    #[wasm_bindgen(js_name=withMemory)]
+    pub fn with_memory(megabytes: usize) -> Result<WASMSolver, JsError> {
+        Ok(WASMSolver(build_solver(megabytes)?))
+    }
+
     /// A counter for the number of nodes explored in the last `solve` call.
     #[wasm_bindgen(getter)]
     pub fn explored_positions(&self) -> usize {
         self.0.explored_positions
     }
 
+    /// The number of entries in the solver's transposition table.
+    #[wasm_bindgen(getter, js_name=transpositionTableSize)]
+    pub fn transposition_table_size(&self) -> usize {
+        self.0.transposition_table.size()
+    }
+
+    /// Whether `reset` releases the memory used by the solver's transposition table.
+    ///
+    /// The table is allocated by the first search which needs it, so releasing it stops idle
+    /// solvers from holding on to its memory.
+    #[wasm_bindgen(getter, js_name=releaseTableOnReset)]
+    pub fn release_table_on_reset(&self) -> bool {
+        self.0.release_table_on_reset
+    }
+
+    #[wasm_bindgen(setter, js_name=releaseTableOnReset)]
+    pub fn set_release_table_on_reset(&mut self, release: bool) {
+        self.0.release_table_on_reset = release;
+    }
+
     /// Attempts to load an opening book from the given path.
     ///
     /// Returns whether the opening book was successfully loaded.
@@ -39,6 +67,16 @@ impl WASMSolver {
         self.0.load_opening_book(Path::new(path))
     }
 
+    /// Enables progress messages, which are posted using the global `postMessage` function
+    /// during each search. This is intended for solvers running inside a Web Worker.
+    ///
+    /// A message is posted at each iteration of the binary search, and after every
+    /// `nodeInterval` explored nodes.
+    #[wasm_bindgen(js_name=enableProgressMessages)]
+    pub fn enable_progress_messages(&mut self, node_interval: usize) {
+        self.0.set_observer(WASMProgressObserver::new(node_interval));
+    }
+
     /// Resets the solver's state.
     pub fn reset(&mut self) {
         self.0.reset();
@@ -68,6 +106,20 @@ impl WASMSolver {
         self.0.solve(&position.0)
     }
 
+    /// Solves a position to find its exact score, starting from a narrow window around an
+    /// expected score.
+    ///
+    /// The exact score is always found, but it is found fastest when the hint is correct.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The board position to solve.
+    /// * `expected_score`: The expected score of the position.
+    #[wasm_bindgen(js_name=solveWithHint)]
+    pub fn solve_with_hint(&mut self, position: &WASMPosition, expected_score: i8) -> i8 {
+        self.0.solve_with_hint(&position.0, expected_score)
+    }
+
     /// Calculates the scores for all possible next moves in the given position.
     ///
     /// Returns a fixed-size array where each index corresponds to a column, containing
@@ -85,4 +137,46 @@ impl WASMSolver {
             })
             .collect()
     }
+
+    /// The name of the solver as an engine, allowing solvers to be used as AI player engines.
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        Engine::name(&self.0).to_string()
+    }
+
+    /// Evaluates a position to find its exact score, which is equivalent to `solve`.
+    pub fn evaluate(&mut self, position: &WASMPosition) -> i8 {
+        self.0.evaluate(&position.0)
+    }
+
+    /// Calculates the scores for all possible next moves in the given position, which is
+    /// equivalent to `getAllMoveScores`.
+    #[wasm_bindgen(js_name=moveScores)]
+    pub fn move_scores(&mut self, position: &WASMPosition) -> Vec<JsValue> {
+        self.get_all_move_scores(position)
+    }
+
+    /// Returns the optimal move in the given position, or `undefined` if no moves are possible.
+    ///
+    /// Ties are broken in favour of columns closer to the centre.
+    #[wasm_bindgen(js_name=bestMove)]
+    pub fn best_move(&mut self, position: &WASMPosition) -> Option<usize> {
+        self.0.best_move(&position.0)
+    }
+}
+
+/// Creates a solver using the pre-packaged opening book, with a memory budget for its
+/// transposition table in megabytes.
+pub fn build_solver(megabytes: usize) -> Result<Solver, JsError> {
+    SolverBuilder::new()
+        .transposition_table_memory(megabytes)
+        .build()
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Default constructor for the `WASMSolver` struct.
+impl Default for WASMSolver {
+    fn default() -> WASMSolver {
+        WASMSolver::new()
+    }
 }
\ No newline at end of file